@@ -0,0 +1,76 @@
+//! Compares the owned ([`gdl90::read_raw`]) and borrowed ([`Gdl90MessageRef`]) decoding paths.
+//!
+//! Run with `cargo bench`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use gdl90::borrowed::Gdl90MessageRef;
+use gdl90::types::uplink_data::UPLINK_APPLICATION_DATA_LEN;
+
+const HEARTBEAT: &[u8] = b"\x7E\x00\x81\x41\xDB\xD0\x08\x02\xB3\x8B\x7E";
+
+const OWNSHIP_REPORT: &[u8] = b"\x7E\x0A\x00\x00\x00\x00\x15\xA7\xE5\xBA\x47\x99\x08\xC9\x88\xFF\xE0\x00\x80\x01\x4E\x31\x32\x33\x34\x35\x20\x20\x00\x7B\xE5\x7E";
+
+/// Uplink Data message with a FIS-B Information Frame.
+fn uplink_data() -> Vec<u8> {
+    let mut payload = vec![0x01, 0x02, 0x03];
+    payload.extend_from_slice(&[0x35, 0x5A, 0x5F, 0x3C, 0xB0, 0x7E, 0x20, 0x00]);
+    let mut application_data = [0u8; UPLINK_APPLICATION_DATA_LEN];
+    application_data[..5].copy_from_slice(&[0x01, 0x80, 0x7D, 0x7E, 0x00]);
+    payload.extend_from_slice(&application_data);
+    gdl90::frame(0x07, &payload)
+}
+
+fn bench_message(c: &mut Criterion, name: &str, frame: &[u8]) {
+    let mut group = c.benchmark_group(name);
+
+    group.bench_function("read_raw", |b| {
+        b.iter(|| gdl90::read_raw(black_box(frame)).unwrap())
+    });
+
+    group.bench_function("ref_parse", |b| {
+        b.iter(|| {
+            Gdl90MessageRef::parse(black_box(frame))
+                .unwrap()
+                .message_id()
+        })
+    });
+
+    group.bench_function("ref_parse_in_place", |b| {
+        let mut buffer = frame.to_vec();
+        b.iter(|| {
+            buffer.copy_from_slice(frame);
+            Gdl90MessageRef::parse_in_place(black_box(&mut buffer))
+                .unwrap()
+                .message_id()
+        })
+    });
+
+    group.bench_function("ref_decode", |b| {
+        b.iter(|| {
+            Gdl90MessageRef::parse(black_box(frame))
+                .unwrap()
+                .decode()
+                .unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+    bench_message(c, "heartbeat", HEARTBEAT);
+    bench_message(c, "ownship_report", OWNSHIP_REPORT);
+
+    let uplink = uplink_data();
+    bench_message(c, "uplink_data", &uplink);
+
+    c.bench_function("uplink_data/ref_information_frames", |b| {
+        b.iter(|| {
+            let message = Gdl90MessageRef::parse(black_box(&uplink)).unwrap();
+            message.uplink_data().unwrap().unwrap().information_frames()
+        })
+    });
+}
+
+criterion_group!(benches, decode);
+criterion_main!(benches);
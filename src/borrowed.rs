@@ -0,0 +1,420 @@
+//! Borrowed GDL90 frame decoding, for high-rate streams.
+//!
+//! [`Gdl90MessageRef`] validates a frame and references its message data in the received buffer.
+//! The data is only copied when the frame holds escaped bytes, and never with
+//! [`Gdl90MessageRef::parse_in_place`]. Uplink Data and UAT ADS-B reports can be inspected
+//! without copying their payloads, any message can be fully decoded with
+//! [`Gdl90MessageRef::decode`].
+//!
+//! ## Example
+//! ```
+//! use gdl90::borrowed::Gdl90MessageRef;
+//! use gdl90::datalink::Gdl90DatalinkMessage;
+//!
+//! let frame = [0x7E, 0x00, 0x81, 0x41, 0xDB, 0xD0, 0x08, 0x02, 0xB3, 0x8B, 0x7E];
+//! let message = Gdl90MessageRef::parse(&frame).unwrap();
+//! assert!(message.is_borrowed());
+//! assert_eq!(message.message_id(), 0x00);
+//! assert_eq!(message.payload(), [0x81, 0x41, 0xDB, 0xD0, 0x08, 0x02]);
+//! assert!(matches!(message.decode().unwrap(), Gdl90DatalinkMessage::Heartbeat { .. }));
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use binrw::io::Cursor;
+use binrw::Endian;
+
+use crate::datalink::Gdl90DatalinkMessage;
+use crate::error::Gdl90Error;
+use crate::types::uat_adsb::{UatAdsbPayload, UAT_BASIC_PAYLOAD_LEN, UAT_LONG_PAYLOAD_LEN};
+use crate::types::uplink_data::{
+    fisb_apdus, information_frames, FisbApdu, InformationFrame, UplinkHeader, UplinkPayload,
+    UPLINK_APPLICATION_DATA_LEN,
+};
+use crate::{
+    check_crc, parse_message_data, remove_escapes_in_place, Gdl90Message, GDL90_ESCAPEBYTE,
+    GDL90_MAGIC,
+};
+
+/// Uplink Data message ID.
+const UPLINK_DATA_ID: u8 = 0x07;
+
+/// Basic UAT ADS-B Report message ID.
+const BASIC_REPORT_ID: u8 = 0x1E;
+
+/// Long UAT ADS-B Report message ID.
+const LONG_REPORT_ID: u8 = 0x1F;
+
+/// Length of the Time of Reception field.
+const TIME_OF_RECEPTION_LEN: usize = 3;
+
+/// GDL90 message whose data is borrowed from the received frame when possible.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Gdl90MessageRef<'a> {
+    /// Unescaped message id and data, without the CRC.
+    data: Cow<'a, [u8]>,
+
+    /// Frame Check Sequence, already validated.
+    frame_check_seq: u16,
+}
+
+impl<'a> Gdl90MessageRef<'a> {
+    /// Parses the frame at the start of `frame`, flags included, validating its CRC.
+    ///
+    /// The message data is borrowed from `frame`, unless it holds escaped bytes.
+    /// Bytes after the end flag are ignored, like [`crate::read_raw`].
+    pub fn parse(frame: &'a [u8]) -> Result<Self, Gdl90Error> {
+        let end = frame_end(frame)?;
+        let bytes = &frame[1..end];
+        let bytes = match bytes.contains(&GDL90_ESCAPEBYTE) {
+            true => {
+                let mut bytes = bytes.to_vec();
+                let len = remove_escapes_in_place(&mut bytes);
+                bytes.truncate(len);
+                Cow::Owned(bytes)
+            }
+            false => Cow::Borrowed(bytes),
+        };
+        Self::from_unescaped(bytes)
+    }
+
+    /// Same as [`Gdl90MessageRef::parse`], but unescapes the frame in place so the message
+    /// data is always borrowed. The content of `frame` is unspecified afterwards.
+    pub fn parse_in_place(frame: &'a mut [u8]) -> Result<Self, Gdl90Error> {
+        let end = frame_end(frame)?;
+        let bytes = &mut frame[1..end];
+        let len = remove_escapes_in_place(bytes);
+        let bytes: &'a [u8] = &bytes[..len];
+        Self::from_unescaped(Cow::Borrowed(bytes))
+    }
+
+    /// Validates the CRC of the unescaped `bytes` and removes it.
+    fn from_unescaped(mut bytes: Cow<'a, [u8]>) -> Result<Self, Gdl90Error> {
+        let frame_check_seq = check_crc(&bytes)?;
+        let len = bytes.len() - 2;
+        match &mut bytes {
+            Cow::Borrowed(bytes) => *bytes = &bytes[..len],
+            Cow::Owned(bytes) => bytes.truncate(len),
+        }
+        Ok(Self {
+            data: bytes,
+            frame_check_seq,
+        })
+    }
+
+    /// Message ID.
+    pub fn message_id(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// Message data, without the message ID and CRC.
+    pub fn payload(&self) -> &[u8] {
+        &self.data[1..]
+    }
+
+    /// Frame Check Sequence.
+    pub fn frame_check_seq(&self) -> u16 {
+        self.frame_check_seq
+    }
+
+    /// Whether the message data is borrowed from the frame, ie. it was not copied.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.data, Cow::Borrowed(_))
+    }
+
+    /// Copies the message data if it is borrowed, so the message outlives the frame.
+    pub fn into_owned(self) -> Gdl90MessageRef<'static> {
+        Gdl90MessageRef {
+            data: Cow::Owned(self.data.into_owned()),
+            frame_check_seq: self.frame_check_seq,
+        }
+    }
+
+    /// Decodes the message, same as reading the frame with [`crate::read_raw`].
+    pub fn decode(&self) -> Result<Gdl90DatalinkMessage, Gdl90Error> {
+        Ok(parse_message_data(
+            &mut Cursor::new(&self.data[..]),
+            Endian::Little,
+            (),
+        )?)
+    }
+
+    /// Decodes the message into a [`Gdl90Message`].
+    pub fn to_message(&self) -> Result<Gdl90Message, Gdl90Error> {
+        Ok(Gdl90Message {
+            message_data: self.decode()?,
+            frame_check_seq: self.frame_check_seq,
+        })
+    }
+
+    /// Borrowed Uplink Data, `None` if this is not an Uplink Data message.
+    pub fn uplink_data(&self) -> Option<Result<UplinkDataRef<'_>, Gdl90Error>> {
+        if self.message_id() != UPLINK_DATA_ID {
+            return None;
+        }
+
+        let (time_of_reception, payload) = match split_time_of_reception(self.payload()) {
+            Ok(x) => x,
+            Err(err) => return Some(Err(err)),
+        };
+        let Some((header, application_data)) = payload.split_first_chunk::<8>() else {
+            return Some(Err(Gdl90Error::Truncated));
+        };
+        let Some((application_data, _)) =
+            application_data.split_first_chunk::<UPLINK_APPLICATION_DATA_LEN>()
+        else {
+            return Some(Err(Gdl90Error::Truncated));
+        };
+
+        Some(Ok(UplinkDataRef {
+            time_of_reception,
+            header: UplinkHeader(*header),
+            application_data,
+        }))
+    }
+
+    /// Borrowed Basic or Long UAT ADS-B Report, `None` if this is not one of them.
+    pub fn uat_report(&self) -> Option<Result<UatReportRef<'_>, Gdl90Error>> {
+        let len = match self.message_id() {
+            BASIC_REPORT_ID => UAT_BASIC_PAYLOAD_LEN,
+            LONG_REPORT_ID => UAT_LONG_PAYLOAD_LEN,
+            _ => return None,
+        };
+
+        Some(
+            split_time_of_reception(self.payload()).and_then(|(time_of_reception, payload)| {
+                let payload = payload.get(..len).ok_or(Gdl90Error::Truncated)?;
+                Ok(UatReportRef {
+                    time_of_reception,
+                    payload,
+                })
+            }),
+        )
+    }
+}
+
+/// Borrowed Uplink Data message, see [`Gdl90DatalinkMessage::UplinkData`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct UplinkDataRef<'a> {
+    /// 24-bit binary fraction, resolution = 80 nsec.
+    pub time_of_reception: u32,
+
+    /// UAT-Specific Header.
+    pub header: UplinkHeader,
+
+    /// Application Data, holding the Information Frames.
+    pub application_data: &'a [u8; UPLINK_APPLICATION_DATA_LEN],
+}
+
+impl UplinkDataRef<'_> {
+    /// Splits the Application Data into its Information Frames, see
+    /// [`UplinkPayload::information_frames`].
+    pub fn information_frames(&self) -> Vec<InformationFrame> {
+        information_frames(&self.header, self.application_data)
+    }
+
+    /// Decodes every FIS-B APDU of the Application Data.
+    pub fn fisb_apdus(&self) -> Vec<Result<FisbApdu, Gdl90Error>> {
+        fisb_apdus(&self.header, self.application_data)
+    }
+
+    /// Copies the payload into an [`UplinkPayload`].
+    pub fn to_payload(&self) -> UplinkPayload {
+        UplinkPayload {
+            header: self.header,
+            application_data: *self.application_data,
+        }
+    }
+}
+
+/// Borrowed UAT ADS-B Report, see [`Gdl90DatalinkMessage::BasicReport`] and
+/// [`Gdl90DatalinkMessage::LongReport`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct UatReportRef<'a> {
+    /// 24-bit binary fraction, resolution = 80 nsec.
+    pub time_of_reception: u32,
+
+    /// UAT ADS-B payload, 18 bytes for a Basic Report and 34 bytes for a Long Report.
+    pub payload: &'a [u8],
+}
+
+impl UatReportRef<'_> {
+    /// Decodes the payload into an [`UatAdsbPayload`].
+    pub fn to_payload(&self) -> Result<UatAdsbPayload, Gdl90Error> {
+        use binrw::BinRead;
+        let len = self.payload.len();
+        Ok(UatAdsbPayload::read_args(
+            &mut Cursor::new(self.payload),
+            (len,),
+        )?)
+    }
+}
+
+/// Returns the index of the end flag of the frame starting at `frame[0]`.
+fn frame_end(frame: &[u8]) -> Result<usize, Gdl90Error> {
+    if frame.first() != Some(&GDL90_MAGIC) {
+        return Err(Gdl90Error::MissingFlag);
+    }
+    frame[1..]
+        .iter()
+        .position(|&b| b == GDL90_MAGIC)
+        .map(|end| end + 1)
+        .ok_or(Gdl90Error::MissingFlag)
+}
+
+/// Splits the 24-bit, LSB first, Time of Reception from the rest of `payload`.
+fn split_time_of_reception(payload: &[u8]) -> Result<(u32, &[u8]), Gdl90Error> {
+    if payload.len() < TIME_OF_RECEPTION_LEN {
+        return Err(Gdl90Error::Truncated);
+    }
+    let (time, rest) = payload.split_at(TIME_OF_RECEPTION_LEN);
+    Ok((u32::from_le_bytes([time[0], time[1], time[2], 0]), rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{frame, read_raw};
+
+    const HEARTBEAT: &[u8] = b"\x7E\x00\x81\x41\xDB\xD0\x08\x02\xB3\x8B\x7E";
+
+    fn uplink_frame() -> Vec<u8> {
+        let mut payload = vec![0x01, 0x02, 0x03];
+        // header with application data valid
+        payload.extend_from_slice(&[0x35, 0x5A, 0x5F, 0x3C, 0xB0, 0x7E, 0x20, 0x00]);
+        let mut application_data = [0u8; UPLINK_APPLICATION_DATA_LEN];
+        // one FIS-B frame of 3 bytes
+        application_data[..5].copy_from_slice(&[0x01, 0x80, 0x7D, 0x7E, 0x00]);
+        payload.extend_from_slice(&application_data);
+        frame(UPLINK_DATA_ID, &payload)
+    }
+
+    #[test]
+    fn parse_borrows() {
+        let message = Gdl90MessageRef::parse(HEARTBEAT).unwrap();
+        assert!(message.is_borrowed());
+        assert_eq!(message.message_id(), 0x00);
+        assert_eq!(message.payload(), [0x81, 0x41, 0xDB, 0xD0, 0x08, 0x02]);
+        assert_eq!(message.frame_check_seq(), 0x8bb3);
+        assert_eq!(message.to_message().unwrap(), read_raw(HEARTBEAT).unwrap());
+
+        // trailing bytes are ignored
+        let data = [HEARTBEAT, HEARTBEAT].concat();
+        assert_eq!(Gdl90MessageRef::parse(&data).unwrap(), message);
+    }
+
+    #[test]
+    fn parse_escaped() {
+        let data = frame(0x66, &[0x7E, 0x01, 0x7D]);
+        let message = Gdl90MessageRef::parse(&data).unwrap();
+        assert!(!message.is_borrowed());
+        assert_eq!(message.message_id(), 0x66);
+        assert_eq!(message.payload(), [0x7E, 0x01, 0x7D]);
+
+        let mut in_place = data.clone();
+        let in_place = Gdl90MessageRef::parse_in_place(&mut in_place).unwrap();
+        assert!(in_place.is_borrowed());
+        assert_eq!(in_place, message);
+
+        let owned = message.clone().into_owned();
+        assert_eq!(owned, message);
+        assert_eq!(
+            owned.decode().unwrap(),
+            read_raw(&data).unwrap().message_data
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let mut data = HEARTBEAT.to_vec();
+        data[8] = 0xFF;
+        assert!(matches!(
+            Gdl90MessageRef::parse(&data),
+            Err(Gdl90Error::BadCrc {
+                expected: 0x8bb3,
+                ..
+            })
+        ));
+        assert!(matches!(
+            Gdl90MessageRef::parse(&HEARTBEAT[1..]),
+            Err(Gdl90Error::MissingFlag)
+        ));
+        assert!(matches!(
+            Gdl90MessageRef::parse(&HEARTBEAT[..10]),
+            Err(Gdl90Error::MissingFlag)
+        ));
+        assert!(matches!(
+            Gdl90MessageRef::parse(&[0x7E, 0x00, 0x00, 0x7E]),
+            Err(Gdl90Error::Truncated)
+        ));
+
+        // heartbeat without message counts
+        let data = frame(0x00, &[0x81, 0x41, 0xDB, 0xD0]);
+        let message = Gdl90MessageRef::parse(&data).unwrap();
+        assert!(matches!(message.decode(), Err(Gdl90Error::Truncated)));
+    }
+
+    #[test]
+    fn uplink_data_works() {
+        let data = uplink_frame();
+        let message = Gdl90MessageRef::parse(&data).unwrap();
+        let uplink = message.uplink_data().unwrap().unwrap();
+        assert_eq!(uplink.time_of_reception, 0x030201);
+        assert!(uplink.header.app_data_valid());
+        assert_eq!(uplink.application_data[2..5], [0x7D, 0x7E, 0x00]);
+        assert_eq!(uplink.information_frames().len(), 1);
+
+        let Gdl90DatalinkMessage::UplinkData {
+            time_of_reception,
+            payload,
+        } = read_raw(&data).unwrap().message_data
+        else {
+            panic!("Expected UplinkData message");
+        };
+        assert_eq!(time_of_reception, uplink.time_of_reception);
+        assert_eq!(uplink.to_payload(), payload);
+        assert_eq!(uplink.information_frames(), payload.information_frames());
+
+        // other messages
+        let message = Gdl90MessageRef::parse(HEARTBEAT).unwrap();
+        assert!(message.uplink_data().is_none());
+        assert!(message.uat_report().is_none());
+
+        // truncated
+        let data = frame(UPLINK_DATA_ID, &[0x00; 100]);
+        let message = Gdl90MessageRef::parse(&data).unwrap();
+        assert!(matches!(
+            message.uplink_data(),
+            Some(Err(Gdl90Error::Truncated))
+        ));
+    }
+
+    #[test]
+    fn uat_report_works() {
+        let mut payload = vec![0x10, 0x20, 0x30];
+        payload.extend_from_slice(&[0x00; UAT_BASIC_PAYLOAD_LEN]);
+        let data = frame(BASIC_REPORT_ID, &payload);
+        let message = Gdl90MessageRef::parse(&data).unwrap();
+        let report = message.uat_report().unwrap().unwrap();
+        assert_eq!(report.time_of_reception, 0x302010);
+        assert_eq!(report.payload.len(), UAT_BASIC_PAYLOAD_LEN);
+
+        let Gdl90DatalinkMessage::BasicReport {
+            time_of_reception,
+            payload,
+        } = read_raw(&data).unwrap().message_data
+        else {
+            panic!("Expected BasicReport message");
+        };
+        assert_eq!(time_of_reception, report.time_of_reception);
+        assert_eq!(report.to_payload().unwrap(), payload);
+
+        let data = frame(LONG_REPORT_ID, &[0x00; 10]);
+        let message = Gdl90MessageRef::parse(&data).unwrap();
+        assert!(matches!(
+            message.uat_report(),
+            Some(Err(Gdl90Error::Truncated))
+        ));
+    }
+}
@@ -20,36 +20,65 @@
 //! let object = OperationModeMessage {
 //!      mode: ModeField::ModeA,
 //!      ident: IdentField::Enabled,
-//!      squawk: 2345,
+//!      squawk: Squawk::new(2345).unwrap(),
 //!      emergency: EmergencyField::None,
 //!      healthy: HealthyField::Healthy,
 //! };
 //! assert_eq!(object.to_string_message(), "^MD A,I,23450120\r");
 //! // write to transponder..
+//!
+//! // or without allocating
+//! let mut buffer = [0; CONTROL_MAX_MESSAGE_LEN];
+//! let len = object.write_message(&mut buffer).unwrap();
+//! assert_eq!(&buffer[..len], b"^MD A,I,23450120\r");
+//! ```
+//!
+//! Messages can also be parsed, eg. when emulating the transponder side, with [`ControlMessage::parse`]
+//! or from a stream of bytes with [`ControlDecoder`]:
+//! ```
+//! use gdl90::control::*;
+//!
+//! let mut decoder = ControlDecoder::new();
+//! let message = decoder.decode(b"^VC 1200DA\r").next().unwrap().unwrap();
+//! assert_eq!(message, ControlMessage::VfrCode(VfrCodeMessage { vfr_code: Squawk::VFR }));
 //! ```
 
-use std::io::{Cursor, Seek, SeekFrom, Write};
+use alloc::string::String;
+use alloc::vec::Vec;
 
+use binrw::io::{Cursor, Seek, SeekFrom, Write};
 use binrw::{binwrite, BinWrite};
 
+use crate::error::ControlError;
+use crate::types::icao::IcaoAddress;
+
 /// Trait that each GDL90 Control Panel Interface type should implement in order to get data to be sent.
 pub trait ToStringMessage {
     /// Converts a GDL90 Control message to string, ready to be sent.
     fn to_string_message(&self) -> String;
 }
 
+/// Same as [`ToStringMessage`], but writes to a caller provided buffer instead of allocating.
+pub trait WriteMessage {
+    /// Writes the message, ready to be sent, to the start of `buffer` and returns its length.
+    ///
+    /// Fails with [`ControlError::BufferTooSmall`] if `buffer` cannot hold it, which never happens
+    /// with [`CONTROL_MAX_MESSAGE_LEN`] bytes.
+    fn write_message(&self, buffer: &mut [u8]) -> Result<usize, ControlError>;
+}
+
 /// The call sign message provides for a user selectable call sign.
 /// - Rate: Every 1 minute or when a change occurs
 /// - Message Length: 15 bytes
 #[binwrite]
 #[bw(little, stream = w, map_stream = Checksum::new)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct CallSignMessage {
     #[bw(calc(b"^CS "))]
     id: &[u8; 4],
 
-    // TODO: fill unfillable bytes with spaces
-    #[bw(map = |x| str_to_eight_digit_ascii(&x))]
-    pub call_sign: String,
+    #[bw(map = |x: &CallSign| x.to_ascii())]
+    pub call_sign: CallSign,
 
     #[bw(calc(w.check()))]
     checksum: [u8; 2],
@@ -58,11 +87,24 @@ pub struct CallSignMessage {
     carriage: u8,
 }
 
+impl CallSignMessage {
+    /// Creates a call sign message holding the US civil registration (eg. `N905WN`) of `address`,
+    /// or `None` if it is outside of the `A00001` to `ADF7C7` block.
+    pub fn from_icao_address(address: IcaoAddress) -> Option<Self> {
+        let call_sign = CallSign::new(&address.n_number()?).ok()?;
+        Some(Self { call_sign })
+    }
+}
+
 impl ToStringMessage for CallSignMessage {
     fn to_string_message(&self) -> String {
-        let mut output = Cursor::new(vec![]);
-        self.write(&mut output).unwrap();
-        String::from_utf8(output.into_inner()).unwrap()
+        to_string_message(self)
+    }
+}
+
+impl WriteMessage for CallSignMessage {
+    fn write_message(&self, buffer: &mut [u8]) -> Result<usize, ControlError> {
+        write_message(self, CALL_SIGN_MESSAGE_LEN, buffer)
     }
 }
 
@@ -71,6 +113,7 @@ impl ToStringMessage for CallSignMessage {
 /// - Message Length: 17 bytes
 #[binwrite]
 #[bw(little, stream = w, map_stream = Checksum::new)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct OperationModeMessage {
     #[bw(calc(b"^MD "))]
     id: &[u8; 4],
@@ -85,8 +128,8 @@ pub struct OperationModeMessage {
     #[bw(calc(b','))]
     comma: u8,
 
-    #[bw(map = |x| u16_to_four_digit_ascii(*x))]
-    pub squawk: u16,
+    #[bw(map = |x: &Squawk| x.to_ascii())]
+    pub squawk: Squawk,
 
     pub emergency: EmergencyField,
 
@@ -101,9 +144,13 @@ pub struct OperationModeMessage {
 
 impl ToStringMessage for OperationModeMessage {
     fn to_string_message(&self) -> String {
-        let mut output = Cursor::new(vec![]);
-        self.write(&mut output).unwrap();
-        String::from_utf8(output.into_inner()).unwrap()
+        to_string_message(self)
+    }
+}
+
+impl WriteMessage for OperationModeMessage {
+    fn write_message(&self, buffer: &mut [u8]) -> Result<usize, ControlError> {
+        write_message(self, OPERATION_MODE_MESSAGE_LEN, buffer)
     }
 }
 
@@ -113,12 +160,13 @@ impl ToStringMessage for OperationModeMessage {
 /// - Message Length 11 bytes
 #[binwrite]
 #[bw(little, stream = w, map_stream = Checksum::new)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct VfrCodeMessage {
     #[bw(calc(b"^VC "))]
     id: &[u8; 4],
 
-    #[bw(map = |x| u16_to_four_digit_ascii(*x))]
-    pub vfr_code: u16,
+    #[bw(map = |x: &Squawk| x.to_ascii())]
+    pub vfr_code: Squawk,
 
     #[bw(calc(w.check()))]
     checksum: [u8; 2],
@@ -129,14 +177,134 @@ pub struct VfrCodeMessage {
 
 impl ToStringMessage for VfrCodeMessage {
     fn to_string_message(&self) -> String {
-        let mut output = Cursor::new(vec![]);
-        self.write(&mut output).unwrap();
-        String::from_utf8(output.into_inner()).unwrap()
+        to_string_message(self)
+    }
+}
+
+impl WriteMessage for VfrCodeMessage {
+    fn write_message(&self, buffer: &mut [u8]) -> Result<usize, ControlError> {
+        write_message(self, VFR_CODE_MESSAGE_LEN, buffer)
+    }
+}
+
+/// Squawk (Mode 3/A) code: four octal digits, from `0000` to `7777`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Squawk(u16);
+
+impl Squawk {
+    /// VFR squawk code in the United States.
+    pub const VFR: Squawk = Squawk(1200);
+
+    /// Creates a squawk code from its four digits (eg. `7500`), rejecting digits 8 and 9.
+    pub fn new(code: u16) -> Result<Self, ControlError> {
+        let mut digits = code;
+        for _ in 0..4 {
+            if digits % 10 > 7 {
+                return Err(ControlError::InvalidField { field: "squawk" });
+            }
+            digits /= 10;
+        }
+        if digits != 0 {
+            return Err(ControlError::InvalidField { field: "squawk" });
+        }
+        Ok(Self(code))
+    }
+
+    /// Returns the four digits of the code (eg. `7500`).
+    pub fn code(self) -> u16 {
+        self.0
+    }
+
+    /// Converts the code to its four digit ASCII-text.
+    fn to_ascii(self) -> [u8; 4] {
+        let mut output = [b'0'; 4];
+        let mut digits = self.0;
+        for byte in output.iter_mut().rev() {
+            *byte += (digits % 10) as u8;
+            digits /= 10;
+        }
+        output
+    }
+}
+
+impl TryFrom<u16> for Squawk {
+    type Error = ControlError;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl core::str::FromStr for Squawk {
+    type Err = ControlError;
+
+    /// Parses four digits, eg. `7500`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(four_digit_ascii_to_u16(s.as_bytes(), "squawk")?)
+    }
+}
+
+impl core::fmt::Display for Squawk {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:04}", self.0)
+    }
+}
+
+/// Call sign: up to 8 characters among `A`-`Z`, `0`-`9` and space.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct CallSign([u8; CallSign::MAX_LEN]);
+
+impl CallSign {
+    /// Maximum number of characters.
+    pub const MAX_LEN: usize = 8;
+
+    /// Creates a call sign, trailing spaces being removed.
+    pub fn new(call_sign: &str) -> Result<Self, ControlError> {
+        let valid = |b: &u8| b.is_ascii_uppercase() || b.is_ascii_digit() || *b == b' ';
+        if call_sign.len() > Self::MAX_LEN || !call_sign.bytes().all(|b| valid(&b)) {
+            return Err(ControlError::InvalidField { field: "call_sign" });
+        }
+        let mut ascii = [b' '; Self::MAX_LEN];
+        ascii[..call_sign.len()].copy_from_slice(call_sign.as_bytes());
+        Ok(Self(ascii))
+    }
+
+    /// Returns the call sign, without trailing spaces.
+    pub fn as_str(&self) -> &str {
+        // only ASCII characters are accepted
+        core::str::from_utf8(&self.0)
+            .unwrap_or_default()
+            .trim_end_matches(' ')
+    }
+
+    /// Converts the call sign to its eight character ASCII-text, padded with spaces.
+    fn to_ascii(self) -> [u8; 8] {
+        self.0
+    }
+}
+
+impl Default for CallSign {
+    fn default() -> Self {
+        Self([b' '; Self::MAX_LEN])
+    }
+}
+
+impl core::str::FromStr for CallSign {
+    type Err = ControlError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl core::fmt::Display for CallSign {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
 /// GDL90 Operating mode field.
-#[derive(BinWrite)]
+#[derive(BinWrite, Debug, PartialEq, Eq, Clone, Copy)]
 #[bw(little, repr = u8)]
 pub enum ModeField {
     /// Standby Mode turns the GDL 90 transmitter off, so that no ADS-B messages are transmitted.
@@ -151,7 +319,7 @@ pub enum ModeField {
 
 /// When enabled, this causes the GDL 90 to include the IDENT
 /// indication in transmitted ADS-B messages for the next 20 seconds.
-#[derive(BinWrite)]
+#[derive(BinWrite, Debug, PartialEq, Eq, Clone, Copy)]
 #[bw(little, repr = u8)]
 pub enum IdentField {
     Enabled = 0x49,  // 'I'
@@ -159,7 +327,7 @@ pub enum IdentField {
 }
 
 /// The Health indication is set to ‘1’ by the control panel to indicate that it is operating normally.
-#[derive(BinWrite)]
+#[derive(BinWrite, Debug, PartialEq, Eq, Clone, Copy)]
 #[bw(little, repr = u8)]
 pub enum HealthyField {
     NotHealthy = 48, // '0'
@@ -167,7 +335,7 @@ pub enum HealthyField {
 }
 
 /// Any active emergency code is included in the GDL 90’s transmitted ADS-B messages.
-#[derive(BinWrite)]
+#[derive(BinWrite, Debug, PartialEq, Eq, Clone, Copy)]
 #[bw(little, repr = u8)]
 pub enum EmergencyField {
     None = 48,    // 0x0
@@ -179,6 +347,254 @@ pub enum EmergencyField {
     Downed = 54,  // 0x6
 }
 
+impl TryFrom<u8> for ModeField {
+    type Error = ControlError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            b'O' => Ok(ModeField::StandBy),
+            b'A' => Ok(ModeField::ModeA),
+            b'C' => Ok(ModeField::ModeC),
+            _ => Err(ControlError::InvalidField { field: "mode" }),
+        }
+    }
+}
+
+impl TryFrom<u8> for IdentField {
+    type Error = ControlError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            b'I' => Ok(IdentField::Enabled),
+            b'-' => Ok(IdentField::Inactive),
+            _ => Err(ControlError::InvalidField { field: "ident" }),
+        }
+    }
+}
+
+impl TryFrom<u8> for HealthyField {
+    type Error = ControlError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            b'0' => Ok(HealthyField::NotHealthy),
+            b'1' => Ok(HealthyField::Healthy),
+            _ => Err(ControlError::InvalidField { field: "healthy" }),
+        }
+    }
+}
+
+impl TryFrom<u8> for EmergencyField {
+    type Error = ControlError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            b'0' => Ok(EmergencyField::None),
+            b'1' => Ok(EmergencyField::General),
+            b'2' => Ok(EmergencyField::Medical),
+            b'3' => Ok(EmergencyField::Fuel),
+            b'4' => Ok(EmergencyField::Com),
+            b'5' => Ok(EmergencyField::Hijack),
+            b'6' => Ok(EmergencyField::Downed),
+            _ => Err(ControlError::InvalidField { field: "emergency" }),
+        }
+    }
+}
+
+/// Length of the Call Sign message, including the carriage return.
+const CALL_SIGN_MESSAGE_LEN: usize = 15;
+
+/// Length of the Operation Mode message, including the carriage return.
+const OPERATION_MODE_MESSAGE_LEN: usize = 17;
+
+/// Length of the VFR Code message, including the carriage return.
+const VFR_CODE_MESSAGE_LEN: usize = 11;
+
+/// Maximum length of a control message, longer lines are discarded by [`ControlDecoder`].
+pub const CONTROL_MAX_MESSAGE_LEN: usize = OPERATION_MODE_MESSAGE_LEN;
+
+/// Any GDL90 Control Panel Interface message.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ControlMessage {
+    CallSign(CallSignMessage),
+    OperationMode(OperationModeMessage),
+    VfrCode(VfrCodeMessage),
+}
+
+impl ControlMessage {
+    /// Parses a single message, from its `^` up to and including its carriage return.
+    ///
+    /// The checksum is verified before any field is decoded.
+    pub fn parse(data: &[u8]) -> Result<Self, ControlError> {
+        let len = match data.get(..4) {
+            Some(b"^CS ") => CALL_SIGN_MESSAGE_LEN,
+            Some(b"^MD ") => OPERATION_MODE_MESSAGE_LEN,
+            Some(b"^VC ") => VFR_CODE_MESSAGE_LEN,
+            _ => {
+                let id = &data[..data.len().min(4)];
+                return Err(ControlError::UnknownId(String::from_utf8_lossy(id).into()));
+            }
+        };
+        if data.len() != len {
+            return Err(ControlError::InvalidLength {
+                expected: len,
+                actual: data.len(),
+            });
+        }
+        if data[len - 1] != b'\r' {
+            return Err(ControlError::InvalidField { field: "carriage" });
+        }
+
+        let (body, checksum) = data[..len - 1].split_at(len - 3);
+        let actual = core::str::from_utf8(checksum)
+            .ok()
+            .filter(|x| x.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|x| u8::from_str_radix(x, 16).ok())
+            .ok_or(ControlError::InvalidField { field: "checksum" })?;
+        let expected = body.iter().fold(0u8, |acc, b| acc.wrapping_add(*b));
+        if expected != actual {
+            return Err(ControlError::BadChecksum { expected, actual });
+        }
+
+        let fields = &body[4..];
+        let message = match data[1] {
+            b'C' => ControlMessage::CallSign(CallSignMessage {
+                call_sign: core::str::from_utf8(fields)
+                    .map_err(|_| ControlError::InvalidField { field: "call_sign" })?
+                    .parse()?,
+            }),
+            b'M' => {
+                if fields[1] != b',' || fields[3] != b',' {
+                    return Err(ControlError::InvalidField { field: "separator" });
+                }
+                ControlMessage::OperationMode(OperationModeMessage {
+                    mode: ModeField::try_from(fields[0])?,
+                    ident: IdentField::try_from(fields[2])?,
+                    squawk: Squawk::new(four_digit_ascii_to_u16(&fields[4..8], "squawk")?)?,
+                    emergency: EmergencyField::try_from(fields[8])?,
+                    healthy: HealthyField::try_from(fields[9])?,
+                })
+            }
+            _ => ControlMessage::VfrCode(VfrCodeMessage {
+                vfr_code: Squawk::new(four_digit_ascii_to_u16(fields, "vfr_code")?)
+                    .map_err(|_| ControlError::InvalidField { field: "vfr_code" })?,
+            }),
+        };
+        Ok(message)
+    }
+}
+
+impl core::str::FromStr for ControlMessage {
+    type Err = ControlError;
+
+    /// Parses a single message, see [`ControlMessage::parse`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s.as_bytes())
+    }
+}
+
+impl ToStringMessage for ControlMessage {
+    fn to_string_message(&self) -> String {
+        to_string_message(self)
+    }
+}
+
+impl WriteMessage for ControlMessage {
+    fn write_message(&self, buffer: &mut [u8]) -> Result<usize, ControlError> {
+        match self {
+            ControlMessage::CallSign(x) => x.write_message(buffer),
+            ControlMessage::OperationMode(x) => x.write_message(buffer),
+            ControlMessage::VfrCode(x) => x.write_message(buffer),
+        }
+    }
+}
+
+/// Stateful decoder that accepts arbitrary byte chunks and yields the control messages found in them.
+///
+/// Messages are delimited by carriage returns, bytes before the `^` of a message being discarded.
+#[derive(Debug, Default)]
+pub struct ControlDecoder {
+    buffer: Vec<u8>,
+}
+
+impl ControlDecoder {
+    /// Creates a new decoder with an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `data` to the internal buffer.
+    pub fn push(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Appends `data` to the internal buffer and returns an iterator over the complete messages.
+    pub fn decode(&mut self, data: &[u8]) -> ControlMessages<'_> {
+        self.push(data);
+        self.messages()
+    }
+
+    /// Returns an iterator over the complete messages in the internal buffer.
+    pub fn messages(&mut self) -> ControlMessages<'_> {
+        ControlMessages { decoder: self }
+    }
+
+    /// Number of buffered bytes which are not part of a complete message yet.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Discards all buffered bytes.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// Decodes the next complete message in the internal buffer.
+    ///
+    /// Returns `None` when more data is needed. Lines without any `^` are discarded, as well as
+    /// lines exceeding [`CONTROL_MAX_MESSAGE_LEN`] before their carriage return is received.
+    pub fn next_message(&mut self) -> Option<Result<ControlMessage, ControlError>> {
+        loop {
+            // resynchronise on the start of a message
+            let Some(start) = self.buffer.iter().position(|&b| b == b'^') else {
+                self.buffer.clear();
+                return None;
+            };
+            self.buffer.drain(..start);
+
+            let Some(end) = self.buffer.iter().position(|&b| b == b'\r') else {
+                if self.buffer.len() > CONTROL_MAX_MESSAGE_LEN {
+                    // carriage return was lost, start over with the remaining bytes
+                    self.buffer.drain(..1);
+                    continue;
+                }
+                return None;
+            };
+
+            // a new message started before the carriage return
+            let line = &self.buffer[..=end];
+            let start = line.iter().rposition(|&b| b == b'^').unwrap_or(0);
+            let result = ControlMessage::parse(&line[start..]);
+            self.buffer.drain(..=end);
+            return Some(result);
+        }
+    }
+}
+
+/// Iterator over the complete messages of a [`ControlDecoder`], see [`ControlDecoder::messages`].
+#[derive(Debug)]
+pub struct ControlMessages<'a> {
+    decoder: &'a mut ControlDecoder,
+}
+
+impl Iterator for ControlMessages<'_> {
+    type Item = Result<ControlMessage, ControlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.decoder.next_message()
+    }
+}
+
 struct Checksum<T> {
     inner: T,
     check: core::num::Wrapping<u8>,
@@ -193,13 +609,34 @@ impl<T> Checksum<T> {
     }
 
     fn check(&self) -> [u8; 2] {
-        let hex_str = format!("{:02X}", self.check.0);
-        let s1 = hex_str.chars().nth(0).unwrap() as u8;
-        let s2 = hex_str.chars().nth(1).unwrap() as u8;
-        [s1, s2]
+        const HEX: &[u8; 16] = b"0123456789ABCDEF";
+        let check = self.check.0 as usize;
+        [HEX[check >> 4], HEX[check & 0x0F]]
     }
 }
 
+/// Writes `message`, which is `len` bytes long, to the start of `buffer`.
+fn write_message<T>(message: &T, len: usize, buffer: &mut [u8]) -> Result<usize, ControlError>
+where
+    T: for<'a> BinWrite<Args<'a> = ()>,
+{
+    let buffer = buffer.get_mut(..len).ok_or(ControlError::BufferTooSmall)?;
+    let mut output = Cursor::new(buffer);
+    // every field is valid, so writing to a buffer of the message length does not fail
+    message
+        .write_options(&mut output, binrw::Endian::Little, ())
+        .map_err(|_| ControlError::BufferTooSmall)?;
+    Ok(len)
+}
+
+/// Converts `message` to string, see [`ToStringMessage`].
+fn to_string_message<T: WriteMessage>(message: &T) -> String {
+    let mut buffer = [0; CONTROL_MAX_MESSAGE_LEN];
+    let len = message.write_message(&mut buffer).unwrap_or_default();
+    // control messages are ASCII-text
+    buffer[..len].iter().map(|&b| char::from(b)).collect()
+}
+
 impl<T: Write> Write for Checksum<T> {
     fn write(&mut self, buf: &[u8]) -> binrw::io::Result<usize> {
         for b in buf {
@@ -219,42 +656,45 @@ impl<T: Seek> Seek for Checksum<T> {
     }
 }
 
-/// Converts `x` as a four digit ASCII-text.
-fn u16_to_four_digit_ascii(x: u16) -> [u8; 4] {
-    let mut s = format!("{:04}", x);
-    s.truncate(4);
-    s.as_bytes().try_into().unwrap_or([0, 0, 0, 0])
-}
-
-/// Converts `x` to a eight digit ASCII-text.
-fn str_to_eight_digit_ascii(x: &str) -> [u8; 8] {
-    let mut buffer = [b' '; 8];
-    let bytes = x.as_bytes();
-
-    let len = bytes.len().min(8);
-    buffer[..len].copy_from_slice(&bytes[..len]);
-
-    buffer.try_into().unwrap_or([0, 0, 0, 0, 0, 0, 0, 0])
+/// Converts a four digit ASCII-text to its value.
+fn four_digit_ascii_to_u16(x: &[u8], field: &'static str) -> Result<u16, ControlError> {
+    if x.len() != 4 || !x.iter().all(u8::is_ascii_digit) {
+        return Err(ControlError::InvalidField { field });
+    }
+    Ok(x.iter().fold(0, |acc, b| acc * 10 + (b - b'0') as u16))
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::borrow::ToOwned;
+    use alloc::string::ToString;
+
     use super::*;
 
     #[test]
     fn call_sign() {
         let object = CallSignMessage {
-            call_sign: "GARMIN".to_owned(),
+            call_sign: CallSign::new("GARMIN").unwrap(),
         };
         assert_eq!(object.to_string_message(), "^CS GARMIN  12\r");
     }
 
+    #[test]
+    fn call_sign_from_icao_address() {
+        let address = IcaoAddress::from_n_number("N905WN").unwrap();
+        let object = CallSignMessage::from_icao_address(address).unwrap();
+        assert_eq!(object.call_sign.as_str(), "N905WN");
+
+        let address = IcaoAddress::new(0x3C6444).unwrap();
+        assert!(CallSignMessage::from_icao_address(address).is_none());
+    }
+
     #[test]
     fn operation_mode() {
         let object = OperationModeMessage {
             mode: ModeField::ModeA,
             ident: IdentField::Enabled,
-            squawk: 2345,
+            squawk: Squawk::new(2345).unwrap(),
             emergency: EmergencyField::None,
             healthy: HealthyField::Healthy,
         };
@@ -263,7 +703,191 @@ mod tests {
 
     #[test]
     fn vfr_code() {
-        let object = VfrCodeMessage { vfr_code: 1200 };
+        let object = VfrCodeMessage {
+            vfr_code: Squawk::VFR,
+        };
         assert_eq!(object.to_string_message(), "^VC 1200DA\r");
     }
+
+    #[test]
+    fn parse_round_trip() {
+        let messages = [
+            ControlMessage::CallSign(CallSignMessage {
+                call_sign: CallSign::new("GARMIN").unwrap(),
+            }),
+            ControlMessage::OperationMode(OperationModeMessage {
+                mode: ModeField::ModeC,
+                ident: IdentField::Inactive,
+                squawk: Squawk::new(7000).unwrap(),
+                emergency: EmergencyField::Medical,
+                healthy: HealthyField::Healthy,
+            }),
+            ControlMessage::VfrCode(VfrCodeMessage {
+                vfr_code: Squawk::VFR,
+            }),
+        ];
+        for message in messages {
+            let text = message.to_string_message();
+            assert_eq!(text.parse::<ControlMessage>(), Ok(message));
+        }
+    }
+
+    #[test]
+    fn write_message_works() {
+        let message = ControlMessage::VfrCode(VfrCodeMessage {
+            vfr_code: Squawk::VFR,
+        });
+        let mut buffer = [0; CONTROL_MAX_MESSAGE_LEN];
+        assert_eq!(message.write_message(&mut buffer), Ok(VFR_CODE_MESSAGE_LEN));
+        assert_eq!(&buffer[..VFR_CODE_MESSAGE_LEN], b"^VC 1200DA\r");
+        assert_eq!(
+            message.write_message(&mut buffer[..VFR_CODE_MESSAGE_LEN - 1]),
+            Err(ControlError::BufferTooSmall)
+        );
+
+        let message = CallSignMessage {
+            call_sign: CallSign::new("GARMIN").unwrap(),
+        };
+        assert_eq!(
+            message.write_message(&mut buffer),
+            Ok(CALL_SIGN_MESSAGE_LEN)
+        );
+        assert_eq!(&buffer[..CALL_SIGN_MESSAGE_LEN], b"^CS GARMIN  12\r");
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            ControlMessage::parse(b"^XX 1200DA\r"),
+            Err(ControlError::UnknownId("^XX ".to_owned()))
+        );
+        assert_eq!(
+            ControlMessage::parse(b"^V"),
+            Err(ControlError::UnknownId("^V".to_owned()))
+        );
+        assert_eq!(
+            ControlMessage::parse(b"^VC 12000DA\r"),
+            Err(ControlError::InvalidLength {
+                expected: 11,
+                actual: 12
+            })
+        );
+        assert_eq!(
+            ControlMessage::parse(b"^VC 1200DA"),
+            Err(ControlError::InvalidLength {
+                expected: 11,
+                actual: 10
+            })
+        );
+        assert_eq!(
+            ControlMessage::parse(b"^VC 1200DB\r"),
+            Err(ControlError::BadChecksum {
+                expected: 0xDA,
+                actual: 0xDB
+            })
+        );
+        assert_eq!(
+            ControlMessage::parse(b"^VC 1200XY\r"),
+            Err(ControlError::InvalidField { field: "checksum" })
+        );
+        assert_eq!(
+            ControlMessage::parse(b"^MD X,I,23450120\r"),
+            Err(ControlError::BadChecksum {
+                expected: 0x37,
+                actual: 0x20
+            })
+        );
+
+        // valid checksums, invalid fields
+        for (body, field) in [
+            ("^MD X,I,234501", "mode"),
+            ("^MD A,X,234501", "ident"),
+            ("^MD A,I,234591", "emergency"),
+            ("^MD A,I,234502", "healthy"),
+            ("^MD A;I,234501", "separator"),
+            ("^MD A,I;234501", "separator"),
+            ("^MD A,I,2X4501", "squawk"),
+            ("^MD A,I,234801", "squawk"),
+            ("^VC 12A4", "vfr_code"),
+            ("^CS n825v   ", "call_sign"),
+        ] {
+            assert_eq!(
+                ControlMessage::parse(with_checksum(body).as_bytes()),
+                Err(ControlError::InvalidField { field }),
+                "{body}"
+            );
+        }
+    }
+
+    /// Appends the checksum and carriage return to the message `body`.
+    fn with_checksum(body: &str) -> String {
+        let checksum = body.bytes().fold(0u8, |acc, b| acc.wrapping_add(b));
+        format!("{body}{checksum:02X}\r")
+    }
+
+    #[test]
+    fn decoder_works() {
+        let mut decoder = ControlDecoder::new();
+        let data = b"noise^CS GARMIN  12\r^VC 1200DB\r^MD A,I,2345";
+        let messages: Vec<_> = decoder.decode(data).collect();
+        assert_eq!(messages.len(), 2);
+        assert!(matches!(messages[0], Ok(ControlMessage::CallSign(_))));
+        assert!(matches!(messages[1], Err(ControlError::BadChecksum { .. })));
+        assert_eq!(decoder.buffered(), 12);
+
+        let message = decoder.decode(b"0120\r").next().unwrap().unwrap();
+        assert!(matches!(message, ControlMessage::OperationMode(_)));
+        assert_eq!(decoder.buffered(), 0);
+
+        // truncated message followed by a complete one
+        let messages: Vec<_> = decoder.decode(b"^VC 12^VC 1200DA\r").collect();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].is_ok());
+    }
+
+    #[test]
+    fn squawk_validation() {
+        assert_eq!(Squawk::new(7500).unwrap().code(), 7500);
+        assert_eq!(Squawk::new(7).unwrap().to_string(), "0007");
+        assert_eq!("7777".parse::<Squawk>().unwrap().code(), 7777);
+        for code in [8, 90, 1280, 7800, 7778, 10000, u16::MAX] {
+            assert_eq!(
+                Squawk::new(code),
+                Err(ControlError::InvalidField { field: "squawk" })
+            );
+        }
+        assert!("12a4".parse::<Squawk>().is_err());
+        assert!("777".parse::<Squawk>().is_err());
+
+        // invalid codes are rejected when parsing too
+        assert_eq!(
+            ControlMessage::parse(b"^VC 1800DA\r"),
+            Err(ControlError::BadChecksum {
+                expected: 0xE0,
+                actual: 0xDA
+            })
+        );
+        assert_eq!(
+            ControlMessage::parse(b"^VC 1800E0\r"),
+            Err(ControlError::InvalidField { field: "vfr_code" })
+        );
+    }
+
+    #[test]
+    fn call_sign_validation() {
+        assert_eq!(CallSign::new("N825V  ").unwrap().as_str(), "N825V");
+        assert_eq!(CallSign::new("").unwrap().as_str(), "");
+        assert_eq!(CallSign::default().as_str(), "");
+        for call_sign in ["n825v", "N825-V", "ABCDEFGHI", "ÄB"] {
+            assert_eq!(
+                CallSign::new(call_sign),
+                Err(ControlError::InvalidField { field: "call_sign" })
+            );
+        }
+
+        let object = CallSignMessage {
+            call_sign: "AB 12".parse().unwrap(),
+        };
+        assert!(object.to_string_message().starts_with("^CS AB 12   "));
+    }
 }
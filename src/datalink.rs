@@ -11,46 +11,54 @@
 //! | 20 | Traffic Report               | OUT   |
 //! | 30 | Basic Report                 | OUT   |
 //! | 31 | Long Report                  | OUT   |
+//! | 101| ForeFlight extension         | OUT   |
+//!
+//! With the `stratux` feature, the Stratux vendor messages are supported too:
+//!
+//! | ID  | Name                         | I/O   |
+//! |-----|------------------------------|-------|
+//! | 76  | Stratux AHRS                 | OUT   |
+//! | 83  | Stratux Status               | OUT   |
+//! | 204 | Stratux Heartbeat            | OUT   |
 
-use crate::types::heartbeat::HeartbeatStatusByte1;
-use crate::types::heartbeat::HeartbeatStatusByte2;
+use alloc::vec::Vec;
+
+use crate::types::foreflight::ForeFlightMessage;
+use crate::types::heartbeat::Heartbeat;
+use crate::types::height_above_terrain::HeightAboveTerrain;
 use crate::types::initialization::ConfigurationByte1;
 use crate::types::initialization::ConfigurationByte2;
-use crate::types::ownship_geometric_altitude::VerticalMetrics;
+use crate::types::ownship_geometric_altitude::{
+    encode_geo_altitude, VerticalMetrics, GDL90_GEO_ALTITUDE_FACTOR,
+};
 use crate::types::report::Report;
+#[cfg(feature = "stratux")]
+use crate::types::stratux::{StratuxAhrs, StratuxHeartbeat, StratuxStatus};
+use crate::types::uat_adsb::{UatAdsbPayload, UAT_BASIC_PAYLOAD_LEN, UAT_LONG_PAYLOAD_LEN};
 use crate::types::uplink_data::UplinkPayload;
 
-use binrw::binread;
-
-const GDL90_GEO_ALTITUDE_FACTOR: i32 = 5;
+use binrw::{BinRead, BinWrite};
 
 /// GDL90 IN/OUT message types.
-/// TODO: binread for IN messages
-/// TODO: binread for OUT messages
-#[binread]
-#[br(little)]
-#[derive(Debug)]
+// derived rather than using `#[binrw]`, so that feature gated variants are removed before expansion
+#[derive(BinRead, BinWrite, Debug, PartialEq)]
+#[brw(little)]
+#[allow(clippy::large_enum_variant)]
 pub enum Gdl90DatalinkMessage {
     /// (OUT) - GDL90 Heartbeat message. 560-1058-00 Rev A - ref 3.1.
     ///
     /// | Byte # | Name             | Size  | Value                                             |
     /// |--------|------------------|-------|---------------------------------------------------|
     /// | 1      |Message ID        | 1     | 0                                                 |
-    /// | 2      |Status Byte 1     | 1     | see [`HeartbeatStatusByte1`]                      |
-    /// | 3      |Status Byte 2     | 1     | see [`HeartbeatStatusByte2`]                      |
-    /// | 4-5    |Timestamp         | 2     | Seconds since 0000Z, bits 15-0 (LSB byte first)   |
-    /// | 6-7    |Message Counts    | 2     |                                                   |
+    /// | 2-7    |Heartbeat         | 6     | see [`Heartbeat`]                                 |
     /// |        |Total length      | 7     |                                                   |
     ///
-    #[br(little, magic = b"\x00")]
-    Heartbeat {
-        status_byte_1: HeartbeatStatusByte1,
-        status_byte_2: HeartbeatStatusByte2,
-        uat_timestamp: u16,
-        message_counts: u16,
-    },
+    #[brw(little, magic = b"\x00")]
+    Heartbeat { heartbeat: Heartbeat },
 
-    /// (IN TODO) - GDL90 Initialization message. 560-1058-00 Rev A - ref 3.2.
+    /// (IN) - GDL90 Initialization message. 560-1058-00 Rev A - ref 3.2.
+    ///
+    /// Sent by the Display to configure the GDL 90, see [`InitializationBuilder`](crate::types::initialization::InitializationBuilder).
     ///
     /// | Byte # | Name                 | Size  | Value                         |
     /// |--------|----------------------|-------|-------------------------------|
@@ -59,7 +67,7 @@ pub enum Gdl90DatalinkMessage {
     /// | 3      |Configuration Byte 2  | 1     | see [`ConfigurationByte2`]    |
     /// |        |Total length          | 3     |                               |
     ///
-    #[br(magic = b"\x02")]
+    #[brw(magic = b"\x02")]
     Initialization {
         configuration_byte_1: ConfigurationByte1,
         configuration_byte_2: ConfigurationByte2,
@@ -76,14 +84,15 @@ pub enum Gdl90DatalinkMessage {
     /// | 5-436  |Uplink payload    | 432   | see [`UplinkPayload`]                         |
     /// |        |Total length      | 436   |                                               |
     ///
-    #[br(magic = b"\x07")]
+    #[brw(magic = b"\x07")]
     UplinkData {
         #[br(parse_with = binrw::helpers::read_u24)]
+        #[bw(write_with = binrw::helpers::write_u24)]
         time_of_reception: u32,
         payload: UplinkPayload,
     },
 
-    /// (OUT) - GDL90 Height Above Terrain Message. 560-1058-00 Rev A - ref 3.7.
+    /// (IN) - GDL90 Height Above Terrain Message. 560-1058-00 Rev A - ref 3.7.
     ///
     /// The GDL 90 can use the Height Above Terrain information from other on-board equipment that
     /// supports terrain awareness, in order to provide reduced CSA sensitivity at low altitudes.
@@ -91,13 +100,11 @@ pub enum Gdl90DatalinkMessage {
     /// | Byte # | Name                 | Size  | Value                                         |
     /// |--------|----------------------|-------|-----------------------------------------------|
     /// | 1      |Message ID            | 1     | 9                                             |
-    /// | 2-3    |Height Above Terrain  | 2     | Height above terrain. Resolution: 1 foot      |
+    /// | 2-3    |Height Above Terrain  | 2     | see [`HeightAboveTerrain`]                    |
     /// |        |Total length          | 3     |                                               |
     ///
-    #[br(magic = b"\x09")]
-    HeightAboveTerrain {
-        hat: u16, // TODO: custom type
-    },
+    #[brw(magic = b"\x09")]
+    HeightAboveTerrain { hat: HeightAboveTerrain },
 
     /// (OUT) - GDL90 Ownship Report message. 560-1058-00 Rev A - ref 3.4.
     ///
@@ -109,7 +116,7 @@ pub enum Gdl90DatalinkMessage {
     /// | 2-28   |Ownship Report| 27    | see [`Report`]  |
     /// |        |Total length  | 28    |                               |
     ///
-    #[br(little, magic = b"\x0A")]
+    #[brw(little, magic = b"\x0A")]
     OwnshipReport {
         report: Report,
     },
@@ -125,7 +132,7 @@ pub enum Gdl90DatalinkMessage {
     /// | 2-28   |Traffic Report| 27    | see [`Report`]  |
     /// |        |Total length  | 28    |                               |
     ///
-    #[br(magic = b"\x14")]
+    #[brw(magic = b"\x14")]
     TrafficReport {
         report: Report,
     },
@@ -139,22 +146,96 @@ pub enum Gdl90DatalinkMessage {
     /// |--------|----------------------|-------|-------------------------------------------------------------------|
     /// | 1      |Message ID            | 1     | 11                                                                |
     /// | 2-3    |Ownship Geo Altitude  | 2     | Signed altitude in 5 ft. resolution                               |
-    /// | 4-5    |Vertical Metrics      | 2     | Vertical Warning indicator and Vertical Figure of Merit in meters |
+    /// | 4-5    |Vertical Metrics      | 2     | see [`VerticalMetrics`]                                           |
     /// |        |Total length          | 5     |                                                                   |
     ///
-    #[br(magic = b"\x0B")]
+    #[brw(magic = b"\x0B")]
     OwnshipGeoometricAltitude {
-        #[br(big)]
+        #[brw(big)]
         #[br(map = |x: i16| (x as i32) * GDL90_GEO_ALTITUDE_FACTOR)]
+        #[bw(map = |x: &i32| encode_geo_altitude(*x))]
+        /// Altitude in feet, rounded to the nearest 5 ft. when written.
         ownship_geo_altitude: i32,
         vertical_metrics: VerticalMetrics,
     },
 
-    #[br(magic = b"\x1E")]
-    BasicReport(), // TODO ?
+    /// (OUT) - GDL90 Basic UAT ADS-B Report. 560-1058-00 Rev A - ref 3.6.
+    ///
+    /// Pass-through of a Basic UAT ADS-B message received from another aircraft.
+    ///
+    /// | Byte # | Name                 | Size  | Value                                     |
+    /// |--------|----------------------|-------|-------------------------------------------|
+    /// | 1      |Message ID            | 1     | 30                                        |
+    /// | 2-4    |Time of reception     | 3     | 24-bit binary fraction, 80 nsec. LSB      |
+    /// | 5-22   |Payload               | 18    | see [`UatAdsbPayload`]                    |
+    /// |        |Total length          | 22    |                                           |
+    ///
+    #[brw(magic = b"\x1E")]
+    BasicReport {
+        #[br(parse_with = binrw::helpers::read_u24)]
+        #[bw(write_with = binrw::helpers::write_u24)]
+        time_of_reception: u32,
+        #[br(args(UAT_BASIC_PAYLOAD_LEN))]
+        payload: UatAdsbPayload,
+    },
 
-    #[br(magic = b"\x1F")]
-    LongReport(), // TODO ?
+    /// (OUT) - GDL90 Long UAT ADS-B Report. 560-1058-00 Rev A - ref 3.6.
+    ///
+    /// Pass-through of a Long UAT ADS-B message received from another aircraft.
+    ///
+    /// | Byte # | Name                 | Size  | Value                                     |
+    /// |--------|----------------------|-------|-------------------------------------------|
+    /// | 1      |Message ID            | 1     | 31                                        |
+    /// | 2-4    |Time of reception     | 3     | 24-bit binary fraction, 80 nsec. LSB      |
+    /// | 5-38   |Payload               | 34    | see [`UatAdsbPayload`]                    |
+    /// |        |Total length          | 38    |                                           |
+    ///
+    #[brw(magic = b"\x1F")]
+    LongReport {
+        #[br(parse_with = binrw::helpers::read_u24)]
+        #[bw(write_with = binrw::helpers::write_u24)]
+        time_of_reception: u32,
+        #[br(args(UAT_LONG_PAYLOAD_LEN))]
+        payload: UatAdsbPayload,
+    },
 
-    Unknown,
+    /// (OUT) - ForeFlight GDL90 extension message, sent by portable receivers.
+    ///
+    /// | Byte # | Name                 | Size  | Value                                     |
+    /// |--------|----------------------|-------|-------------------------------------------|
+    /// | 1      |Message ID            | 1     | 101 (0x65)                                |
+    /// | 2      |Sub-ID                | 1     | see [`ForeFlightMessage`]                 |
+    /// | 3-N    |Sub-message           | N-2   | see [`ForeFlightMessage`]                 |
+    ///
+    #[brw(magic = b"\x65")]
+    ForeFlight { message: ForeFlightMessage },
+
+    /// (OUT) - Stratux AHRS message, see [`StratuxAhrs`].
+    #[cfg(feature = "stratux")]
+    #[brw(magic = b"\x4C")]
+    StratuxAhrs { ahrs: StratuxAhrs },
+
+    /// (OUT) - Stratux Status message, see [`StratuxStatus`].
+    #[cfg(feature = "stratux")]
+    #[brw(magic = b"\x53")]
+    StratuxStatus { status: StratuxStatus },
+
+    /// (OUT) - Stratux Heartbeat message, see [`StratuxHeartbeat`].
+    #[cfg(feature = "stratux")]
+    #[brw(magic = b"\xCC")]
+    StratuxHeartbeat { heartbeat: StratuxHeartbeat },
+
+    /// Any message whose ID is not supported, kept as received (after CRC validation).
+    ///
+    /// Vendor messages can be decoded from it with a [`VendorRegistry`](crate::vendor::VendorRegistry).
+    /// Supported IDs failing to decode are reported as errors rather than as this variant.
+    #[br(pre_assert(false))]
+    Unknown {
+        /// Message ID.
+        id: u8,
+
+        /// Message data, without the message ID and CRC.
+        #[br(parse_with = binrw::helpers::until_eof)]
+        payload: Vec<u8>,
+    },
 }
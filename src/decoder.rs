@@ -0,0 +1,207 @@
+//! Streaming GDL90 frame decoder.
+//!
+//! Serial ports and UDP datagrams may deliver several concatenated frames, or a frame split
+//! across reads. [`Gdl90Decoder`] buffers the received bytes, resynchronises on [`GDL90_MAGIC`]
+//! flags and yields every complete message.
+//!
+//! ## Example
+//! ```
+//! use gdl90::decoder::Gdl90Decoder;
+//! use gdl90::datalink::Gdl90DatalinkMessage;
+//!
+//! let mut decoder = Gdl90Decoder::new();
+//!
+//! // first read only contains part of the frame
+//! assert!(decoder.decode(&[0x7E, 0x00, 0x81, 0x41, 0xDB]).next().is_none());
+//!
+//! // second read completes it
+//! let message = decoder.decode(&[0xD0, 0x08, 0x02, 0xB3, 0x8B, 0x7E]).next().unwrap().unwrap();
+//! assert!(matches!(message.message_data, Gdl90DatalinkMessage::Heartbeat { .. }));
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::error::Gdl90Error;
+use crate::{read_raw, Gdl90Message, GDL90_MAGIC};
+
+/// Maximum (escaped) length of a frame, including its flags.
+/// The biggest message is Uplink Data (436 bytes), which could double in size when escaped.
+pub const GDL90_MAX_FRAME_LEN: usize = 2 * (436 + 2) + 2;
+
+/// Stateful decoder that accepts arbitrary byte chunks and yields the GDL90 messages found in them.
+#[derive(Debug, Default)]
+pub struct Gdl90Decoder {
+    buffer: Vec<u8>,
+}
+
+impl Gdl90Decoder {
+    /// Creates a new decoder with an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `data` to the internal buffer.
+    pub fn push(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Appends `data` to the internal buffer and returns an iterator over the complete messages.
+    pub fn decode(&mut self, data: &[u8]) -> Messages<'_> {
+        self.push(data);
+        self.messages()
+    }
+
+    /// Returns an iterator over the complete messages in the internal buffer.
+    pub fn messages(&mut self) -> Messages<'_> {
+        Messages { decoder: self }
+    }
+
+    /// Number of buffered bytes which are not part of a complete frame yet.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Discards all buffered bytes.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// Decodes the next complete frame in the internal buffer.
+    ///
+    /// Returns `None` when more data is needed. Bytes before a start flag are discarded,
+    /// as well as frames exceeding [`GDL90_MAX_FRAME_LEN`].
+    pub fn next_message(&mut self) -> Option<Result<Gdl90Message, Gdl90Error>> {
+        loop {
+            // resynchronise on the start flag
+            let Some(start) = self.buffer.iter().position(|&b| b == GDL90_MAGIC) else {
+                self.buffer.clear();
+                return None;
+            };
+            self.buffer.drain(..start);
+
+            let Some(end) = self.buffer[1..].iter().position(|&b| b == GDL90_MAGIC) else {
+                if self.buffer.len() > GDL90_MAX_FRAME_LEN {
+                    // end flag was lost, start over with the remaining bytes
+                    self.buffer.drain(..1);
+                    continue;
+                }
+                return None;
+            };
+            let end = end + 1;
+
+            if end == 1 {
+                // two consecutive flags, the first one ends a previous frame
+                self.buffer.drain(..1);
+                continue;
+            }
+
+            let result = read_raw(&self.buffer[..=end]);
+            // the end flag may also be the start flag of the next frame
+            self.buffer.drain(..end);
+            return Some(result);
+        }
+    }
+}
+
+/// Iterator over the complete messages of a [`Gdl90Decoder`], see [`Gdl90Decoder::messages`].
+#[derive(Debug)]
+pub struct Messages<'a> {
+    decoder: &'a mut Gdl90Decoder,
+}
+
+impl Iterator for Messages<'_> {
+    type Item = Result<Gdl90Message, Gdl90Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.decoder.next_message()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datalink::Gdl90DatalinkMessage;
+
+    const HEARTBEAT: &[u8] = b"\x7E\x00\x81\x41\xDB\xD0\x08\x02\xB3\x8B\x7E";
+    const GEO_ALTITUDE: &[u8] = &[0x7E, 0x0B, 0x00, 0xCA, 0x00, 0x0C, 0xFB, 0x88, 0x7E];
+
+    #[test]
+    fn multiple_frames_per_buffer() {
+        let mut decoder = Gdl90Decoder::new();
+        let data = [HEARTBEAT, GEO_ALTITUDE, HEARTBEAT].concat();
+        let messages: Vec<_> = decoder.decode(&data).collect();
+        assert_eq!(messages.len(), 3);
+        assert!(matches!(
+            messages[0].as_ref().unwrap().message_data,
+            Gdl90DatalinkMessage::Heartbeat { .. }
+        ));
+        assert!(matches!(
+            messages[1].as_ref().unwrap().message_data,
+            Gdl90DatalinkMessage::OwnshipGeoometricAltitude { .. }
+        ));
+        assert!(matches!(
+            messages[2].as_ref().unwrap().message_data,
+            Gdl90DatalinkMessage::Heartbeat { .. }
+        ));
+        assert_eq!(decoder.buffered(), 1);
+    }
+
+    #[test]
+    fn shared_flags() {
+        let mut decoder = Gdl90Decoder::new();
+        let data = [HEARTBEAT, &GEO_ALTITUDE[1..]].concat();
+        assert_eq!(decoder.decode(&data).filter(Result::is_ok).count(), 2);
+    }
+
+    #[test]
+    fn split_frames() {
+        let mut decoder = Gdl90Decoder::new();
+        let data = [HEARTBEAT, GEO_ALTITUDE].concat();
+        let mut count = 0;
+        for chunk in data.chunks(3) {
+            for message in decoder.decode(chunk) {
+                assert!(message.is_ok());
+                count += 1;
+            }
+        }
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn resynchronise() {
+        let mut decoder = Gdl90Decoder::new();
+        // garbage, then a truncated frame without its end flag
+        assert!(decoder.decode(&[0x01, 0x02, 0x03]).next().is_none());
+        assert_eq!(decoder.buffered(), 0);
+
+        let data = [&HEARTBEAT[..5], HEARTBEAT].concat();
+        let messages: Vec<_> = decoder.decode(&data).collect();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].is_err());
+        assert!(messages[1].is_ok());
+    }
+
+    #[test]
+    fn bad_crc_does_not_stop_decoding() {
+        let mut decoder = Gdl90Decoder::new();
+        let data = [
+            b"\x7E\x00\x81\x41\xDB\xD0\x08\x02\xFF\xFF\x7E".as_slice(),
+            HEARTBEAT,
+        ]
+        .concat();
+        let messages: Vec<_> = decoder.decode(&data).collect();
+        assert_eq!(messages.len(), 2);
+        assert!(matches!(messages[0], Err(Gdl90Error::BadCrc { .. })));
+        assert!(messages[1].is_ok());
+    }
+
+    #[test]
+    fn lost_end_flag() {
+        let mut decoder = Gdl90Decoder::new();
+        let mut data = vec![GDL90_MAGIC];
+        data.extend([0x00; GDL90_MAX_FRAME_LEN]);
+        assert!(decoder.decode(&data).next().is_none());
+        assert_eq!(decoder.buffered(), 0);
+        assert!(decoder.decode(HEARTBEAT).next().unwrap().is_ok());
+    }
+}
@@ -0,0 +1,164 @@
+//! GDL90 error types.
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use binrw::io;
+
+/// Errors returned when decoding or encoding GDL90 messages.
+#[derive(Debug)]
+pub enum Gdl90Error {
+    /// The received Frame Check Sequence does not match the one calculated from the message.
+    BadCrc {
+        /// CRC calculated from the message id and data.
+        expected: u16,
+        /// CRC received in the frame.
+        actual: u16,
+    },
+
+    /// The frame ended before the whole message could be read.
+    Truncated,
+
+    /// The start or end flag byte of the frame is missing.
+    MissingFlag,
+
+    /// The message id is not supported, eg. no vendor decoder is registered for it.
+    UnknownMessageId(u8),
+
+    /// A message field holds a value which is not valid.
+    InvalidField {
+        /// Name of the field.
+        field: &'static str,
+        /// Raw value of the field.
+        value: u32,
+    },
+
+    /// The buffer is too small to hold the encoded message.
+    BufferTooSmall,
+
+    /// An error occurred in the underlying reader or writer.
+    Io(io::Error),
+}
+
+impl fmt::Display for Gdl90Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadCrc { expected, actual } => {
+                write!(
+                    f,
+                    "bad checksum: expected {expected:#06x}, got {actual:#06x}"
+                )
+            }
+            Self::Truncated => write!(f, "truncated frame"),
+            Self::MissingFlag => write!(f, "missing frame flag byte"),
+            Self::UnknownMessageId(id) => write!(f, "unknown message id {id:#04x}"),
+            Self::InvalidField { field, value } => {
+                write!(f, "invalid value {value:#x} for field `{field}`")
+            }
+            Self::BufferTooSmall => write!(f, "buffer too small"),
+            Self::Io(err) => write!(f, "i/o error: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Gdl90Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Gdl90Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<binrw::Error> for Gdl90Error {
+    fn from(err: binrw::Error) -> Self {
+        match err {
+            binrw::Error::Custom { err, .. } => match err.downcast::<Gdl90Error>() {
+                Ok(err) => *err,
+                Err(err) => Self::Io(other_error(err.to_string())),
+            },
+            binrw::Error::BadMagic { .. } => Self::MissingFlag,
+            binrw::Error::Io(err) if err.kind() == io::ErrorKind::UnexpectedEof => Self::Truncated,
+            binrw::Error::Io(err) => Self::Io(err),
+            binrw::Error::EnumErrors { variant_errors, .. } => {
+                // the relevant error is the one of the variant whose magic matched
+                variant_errors
+                    .into_iter()
+                    .find(|(name, err)| {
+                        *name != "Unknown"
+                            && !matches!(err.root_cause(), binrw::Error::BadMagic { .. })
+                    })
+                    .map_or(Self::Truncated, |(_, err)| Self::from(err))
+            }
+            binrw::Error::Backtrace(backtrace) => Self::from(*backtrace.error),
+            err => Self::Io(other_error(err.to_string())),
+        }
+    }
+}
+
+/// Builds an I/O error of kind [`io::ErrorKind::Other`]. Without `std`, `message` is dropped.
+fn other_error(message: String) -> io::Error {
+    #[cfg(feature = "std")]
+    return io::Error::other(message);
+
+    #[cfg(not(feature = "std"))]
+    io::Error::new(io::ErrorKind::Other, message)
+}
+
+/// Errors returned when parsing GDL90 Control Panel Interface messages, see [`crate::control`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ControlError {
+    /// The message does not start with a known `^XX ` ID.
+    UnknownId(String),
+
+    /// The message length (including the carriage return) does not match the one of its ID.
+    InvalidLength {
+        /// Length of the messages with this ID.
+        expected: usize,
+        /// Length of the received message.
+        actual: usize,
+    },
+
+    /// The received checksum does not match the algebraic sum of the message bytes.
+    BadChecksum {
+        /// Checksum calculated from the message.
+        expected: u8,
+        /// Checksum received in the message.
+        actual: u8,
+    },
+
+    /// A message field holds a value which is not valid.
+    InvalidField {
+        /// Name of the field.
+        field: &'static str,
+    },
+
+    /// The buffer is too small to hold the encoded message.
+    BufferTooSmall,
+}
+
+impl fmt::Display for ControlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownId(id) => write!(f, "unknown control message id `{id}`"),
+            Self::InvalidLength { expected, actual } => {
+                write!(f, "invalid length: expected {expected} bytes, got {actual}")
+            }
+            Self::BadChecksum { expected, actual } => {
+                write!(f, "bad checksum: expected {expected:02X}, got {actual:02X}")
+            }
+            Self::InvalidField { field } => write!(f, "invalid value for field `{field}`"),
+            Self::BufferTooSmall => write!(f, "buffer too small"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ControlError {}
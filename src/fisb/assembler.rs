@@ -0,0 +1,255 @@
+//! Reassembly of segmented FIS-B products.
+//!
+//! Products which do not fit in a single APDU are split across several APDUs (possibly received
+//! through several [`Gdl90DatalinkMessage::UplinkData`](crate::datalink::Gdl90DatalinkMessage::UplinkData)
+//! messages), each one holding a [`FisbSegment`].
+//!
+//! ## Example
+//! ```
+//! use gdl90::fisb::assembler::ProductAssembler;
+//! # use gdl90::types::uplink_data::{FisbApdu, FisbSegment, FisbTime};
+//! # let segment = |apdu_number, payload: &[u8]| FisbApdu {
+//! #     a_flag: false, g_flag: false, p_flag: false, product_id: 8,
+//! #     time: FisbTime { month: None, day: None, hours: 12, minutes: 0, seconds: None },
+//! #     segment: Some(FisbSegment { product_file_id: 1, product_file_length: 2, apdu_number }),
+//! #     payload: payload.to_vec(),
+//! # };
+//!
+//! let mut assembler = ProductAssembler::new();
+//! assert!(assembler.push(segment(2, b"CD")).is_none());
+//! let apdu = assembler.push(segment(1, b"AB")).unwrap();
+//! assert_eq!(apdu.payload, b"ABCD");
+//! assert!(apdu.segment.is_none());
+//! ```
+
+use alloc::collections::BTreeMap;
+use core::time::Duration;
+
+use crate::types::uplink_data::{FisbApdu, FisbSegment, FisbTime};
+
+/// Default maximum number of incomplete products, see [`ProductAssembler::with_limits`].
+pub const DEFAULT_MAX_PENDING: usize = 256;
+
+/// Default maximum age of incomplete products, see [`ProductAssembler::with_limits`].
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(20 * 60);
+
+/// Seconds in a day, APDU times wrapping around at midnight.
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Collects the segments of FIS-B products until they are complete.
+///
+/// Incomplete products are dropped once older than the maximum age, compared to the time of the
+/// latest APDU, or when there are too many of them (the oldest one being dropped first).
+#[derive(Debug, Clone)]
+pub struct ProductAssembler {
+    max_pending: usize,
+    max_age: Duration,
+
+    /// Time and received segments, by product ID and product file ID.
+    pending: BTreeMap<(u16, u16), (FisbTime, BTreeMap<u16, FisbApdu>)>,
+}
+
+impl ProductAssembler {
+    /// Creates an empty assembler, with [`DEFAULT_MAX_PENDING`] and [`DEFAULT_MAX_AGE`].
+    pub fn new() -> Self {
+        Self::with_limits(DEFAULT_MAX_PENDING, DEFAULT_MAX_AGE)
+    }
+
+    /// Creates an empty assembler, keeping up to `max_pending` incomplete products for up to
+    /// `max_age` (based on the APDU times).
+    pub fn with_limits(max_pending: usize, max_age: Duration) -> Self {
+        Self {
+            max_pending,
+            max_age,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Adds an APDU, returning the complete product if it was the last missing segment.
+    ///
+    /// APDUs without segmentation are complete, and returned as is.
+    /// The returned APDU holds the concatenated payloads and the header of the first segment,
+    /// without any [`FisbSegment`].
+    /// Segments whose APDU number is not within the product file length are dropped.
+    pub fn push(&mut self, apdu: FisbApdu) -> Option<FisbApdu> {
+        let Some(FisbSegment {
+            product_file_id,
+            product_file_length,
+            apdu_number,
+        }) = apdu.segment
+        else {
+            return Some(apdu);
+        };
+        if apdu_number == 0 || apdu_number > product_file_length {
+            return None;
+        }
+
+        let now = apdu.time;
+        self.expire(now);
+
+        let key = (apdu.product_id, product_file_id);
+        let (_, segments) = self
+            .pending
+            .entry(key)
+            .or_insert_with(|| (now, BTreeMap::new()));
+        segments.insert(apdu_number, apdu);
+
+        let complete = (1..=product_file_length).all(|n| segments.contains_key(&n));
+        if !complete {
+            while self.pending.len() > self.max_pending {
+                let oldest = self
+                    .pending
+                    .iter()
+                    .max_by_key(|(_, (time, _))| age(time, &now))
+                    .map(|(key, _)| *key)?;
+                self.pending.remove(&oldest);
+            }
+            return None;
+        }
+
+        let (_, mut segments) = self.pending.remove(&key)?;
+        let mut product = segments.remove(&1)?;
+        product.segment = None;
+        for n in 2..=product_file_length {
+            product.payload.extend(segments.remove(&n)?.payload);
+        }
+        Some(product)
+    }
+
+    /// Drops the incomplete products older than the maximum age at `now`.
+    pub fn expire(&mut self, now: FisbTime) {
+        let max_age = self.max_age;
+        self.pending
+            .retain(|_, (time, _)| age(time, &now) <= max_age);
+    }
+
+    /// Number of products waiting for segments.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Discards all incomplete products.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+impl Default for ProductAssembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Time elapsed from `time` to `now`, within a day.
+///
+/// Times up to 12 hours after `now` are considered as received out of order, their age being 0.
+fn age(time: &FisbTime, now: &FisbTime) -> Duration {
+    let seconds = |t: &FisbTime| {
+        t.hours as u64 * 3600 + t.minutes as u64 * 60 + t.seconds.unwrap_or(0) as u64
+    };
+    let elapsed = (seconds(now) + SECONDS_PER_DAY - seconds(time)) % SECONDS_PER_DAY;
+    match elapsed > SECONDS_PER_DAY / 2 {
+        true => Duration::ZERO,
+        false => Duration::from_secs(elapsed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::uplink_data::test_apdu;
+
+    fn segment(product_file_id: u16, apdu_number: u16) -> Option<FisbSegment> {
+        Some(FisbSegment {
+            product_file_id,
+            product_file_length: 3,
+            apdu_number,
+        })
+    }
+
+    #[test]
+    fn reassembly_works() {
+        let mut assembler = ProductAssembler::new();
+
+        // not segmented
+        let single = test_apdu(8, None, b"XYZ");
+        assert_eq!(assembler.push(single.clone()), Some(single));
+
+        // two products interleaved, one of them with a duplicated segment
+        assert!(assembler.push(test_apdu(8, segment(1, 3), b"5")).is_none());
+        assert!(assembler.push(test_apdu(11, segment(1, 1), b"a")).is_none());
+        assert!(assembler.push(test_apdu(8, segment(1, 1), b"12")).is_none());
+        assert!(assembler.push(test_apdu(8, segment(1, 1), b"12")).is_none());
+        assert!(assembler.push(test_apdu(11, segment(1, 2), b"b")).is_none());
+        assert_eq!(assembler.pending(), 2);
+
+        let product = assembler.push(test_apdu(8, segment(1, 2), b"34")).unwrap();
+        assert_eq!(product.product_id, 8);
+        assert_eq!(product.payload, b"12345");
+        assert_eq!(product.segment, None);
+        assert_eq!(assembler.pending(), 1);
+
+        assembler.clear();
+        assert_eq!(assembler.pending(), 0);
+    }
+
+    #[test]
+    fn out_of_range_segments_are_dropped() {
+        let mut assembler = ProductAssembler::new();
+        assert!(assembler.push(test_apdu(8, segment(1, 0), b"?")).is_none());
+        assert!(assembler.push(test_apdu(8, segment(1, 4), b"!")).is_none());
+        assert_eq!(assembler.pending(), 0);
+
+        assert!(assembler.push(test_apdu(8, segment(1, 1), b"1")).is_none());
+        assert!(assembler.push(test_apdu(8, segment(1, 2), b"2")).is_none());
+        // stray segment of a longer product file with the same ID
+        let stray = FisbSegment {
+            product_file_id: 1,
+            product_file_length: 5,
+            apdu_number: 4,
+        };
+        assert!(assembler.push(test_apdu(8, Some(stray), b"x")).is_none());
+
+        let product = assembler.push(test_apdu(8, segment(1, 3), b"3")).unwrap();
+        assert_eq!(product.payload, b"123");
+        assert_eq!(assembler.pending(), 0);
+    }
+
+    #[test]
+    fn pending_products_are_bounded() {
+        let at = |hours, minutes, product_file_id, apdu_number| {
+            let mut apdu = test_apdu(8, segment(product_file_id, apdu_number), b"x");
+            apdu.time.hours = hours;
+            apdu.time.minutes = minutes;
+            apdu
+        };
+
+        // oldest product dropped when full
+        let mut assembler = ProductAssembler::with_limits(2, Duration::from_secs(600));
+        assert!(assembler.push(at(12, 1, 1, 1)).is_none());
+        assert!(assembler.push(at(12, 0, 2, 1)).is_none());
+        assert!(assembler.push(at(12, 2, 3, 1)).is_none());
+        assert_eq!(assembler.pending(), 2);
+        assert!(assembler.push(at(12, 2, 1, 2)).is_none());
+        assert_eq!(assembler.push(at(12, 2, 1, 3)).unwrap().payload, b"xxx");
+        assert!(assembler.push(at(12, 2, 2, 2)).is_none());
+        assert!(assembler.push(at(12, 2, 2, 3)).is_none());
+
+        // expired after 10 minutes, across midnight
+        let mut assembler = ProductAssembler::with_limits(16, Duration::from_secs(600));
+        assert!(assembler.push(at(23, 55, 1, 1)).is_none());
+        assert!(assembler.push(at(0, 4, 2, 1)).is_none());
+        assert_eq!(assembler.pending(), 2);
+        assembler.expire(at(0, 6, 1, 1).time);
+        assert_eq!(assembler.pending(), 1);
+        // segments received slightly out of order are kept
+        assert!(assembler.push(at(0, 3, 2, 2)).is_none());
+        assert_eq!(assembler.pending(), 1);
+        assert!(assembler.push(at(0, 5, 1, 2)).is_none());
+        assert_eq!(assembler.pending(), 2);
+
+        let mut assembler = ProductAssembler::with_limits(0, Duration::from_secs(600));
+        assert!(assembler.push(at(12, 0, 1, 1)).is_none());
+        assert_eq!(assembler.pending(), 0);
+    }
+}
@@ -0,0 +1,669 @@
+//! FIS-B graphical overlay products (NOTAM-TFR, AIRMET, SIGMET, SUA, G-AIRMET, CWA). DO-358 - ref 2.2.4.
+//!
+//! | Product ID | Name       |
+//! |------------|------------|
+//! | 8          | NOTAM-TFR  |
+//! | 11         | AIRMET     |
+//! | 12         | SIGMET     |
+//! | 13         | SUA        |
+//! | 14         | G-AIRMET   |
+//! | 15         | CWA        |
+//!
+//! The APDU payload starts with a 6-byte product header, followed by the overlay records:
+//!
+//! | Bits  | Name                  |
+//! |-------|-----------------------|
+//! | 0-3   | Record Format         |
+//! | 4-7   | Product Version       |
+//! | 8-11  | Record Count          |
+//! | 12-15 | Reserved              |
+//! | 16-39 | Location (DLAC)       |
+//! | 40-47 | Record Reference      |
+//!
+//! Segmented products must be reassembled first, see [`ProductAssembler`](super::assembler::ProductAssembler).
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::text::decode_dlac;
+use crate::error::Gdl90Error;
+use crate::types::read_bits;
+use crate::types::uplink_data::FisbApdu;
+
+/// Record format of graphical overlay records.
+pub const RECORD_FORMAT_GRAPHICAL: u8 = 8;
+
+/// Resolution (in degrees) of the vertices of polygons, polylines and points.
+const VERTEX_RESOLUTION: f64 = 360.0 / (1 << 19) as f64;
+
+/// Resolution (in degrees) of the centers of circular prisms.
+const CIRCLE_RESOLUTION: f64 = 360.0 / (1 << 18) as f64;
+
+/// Type of a graphical product.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GraphicalProduct {
+    NotamTfr,
+    Airmet,
+    Sigmet,
+    Sua,
+    GAirmet,
+    Cwa,
+}
+
+impl TryFrom<u16> for GraphicalProduct {
+    type Error = Gdl90Error;
+
+    fn try_from(product_id: u16) -> Result<Self, Self::Error> {
+        match product_id {
+            8 => Ok(Self::NotamTfr),
+            11 => Ok(Self::Airmet),
+            12 => Ok(Self::Sigmet),
+            13 => Ok(Self::Sua),
+            14 => Ok(Self::GAirmet),
+            15 => Ok(Self::Cwa),
+            id => Err(Gdl90Error::InvalidField {
+                field: "product_id",
+                value: id as u32,
+            }),
+        }
+    }
+}
+
+/// Graphical overlay record.
+///
+/// | Bits  | Name                                      |
+/// |-------|-------------------------------------------|
+/// | 0-9   | Record Length (bytes)                     |
+/// | 10-23 | Report Number                             |
+/// | 24-30 | Report Year                               |
+/// | 31-34 | Reserved                                  |
+/// | 35-38 | Overlay Record Identifier - 1             |
+/// | 39    | Object Label Flag                         |
+/// | 40-   | Object Label (16 bits, or 9 bytes DLAC)   |
+///
+/// The label is followed by the object flags, type and status, the optional object qualifier,
+/// the applicability options, the start/end times and the geometry.
+#[derive(Debug, PartialEq, Clone)]
+pub struct GraphicalRecord {
+    pub product: GraphicalProduct,
+
+    /// Location identifier of the product.
+    pub location: String,
+
+    pub report_number: u16,
+
+    /// Report year (2 digits).
+    pub report_year: u8,
+
+    pub overlay_record_id: u8,
+
+    pub label: ObjectLabel,
+
+    pub object_element: u8,
+
+    pub object_type: u8,
+
+    pub object_status: u8,
+
+    pub object_qualifier: Option<u32>,
+
+    /// Start of validity, `None` means valid from now.
+    pub start: Option<RecordTime>,
+
+    /// End of validity, `None` means valid until further notice.
+    pub end: Option<RecordTime>,
+
+    pub geometry: Geometry,
+}
+
+impl GraphicalRecord {
+    /// Decodes every overlay record of a graphical product APDU.
+    ///
+    /// Returns [`Gdl90Error::InvalidField`] if the APDU is not a graphical product,
+    /// or does not hold graphical records.
+    pub fn decode(apdu: &FisbApdu) -> Result<Vec<Self>, Gdl90Error> {
+        let product = GraphicalProduct::try_from(apdu.product_id)?;
+        let data = &apdu.payload;
+        if data.len() < 6 {
+            return Err(Gdl90Error::Truncated);
+        }
+
+        let record_format = read_bits(data, 0, 4) as u8;
+        if record_format != RECORD_FORMAT_GRAPHICAL {
+            return Err(Gdl90Error::InvalidField {
+                field: "record_format",
+                value: record_format as u32,
+            });
+        }
+        let record_count = read_bits(data, 8, 4);
+        let location = decode_dlac(&data[2..5]).trim_end().to_owned();
+
+        let mut records = vec![];
+        let mut data = &data[6..];
+        for _ in 0..record_count {
+            if data.len() < 2 {
+                return Err(Gdl90Error::Truncated);
+            }
+            let length = read_bits(data, 0, 10) as usize;
+            if length > data.len() {
+                return Err(Gdl90Error::Truncated);
+            }
+            records.push(Self::decode_record(product, &location, &data[..length])?);
+            data = &data[length..];
+        }
+        Ok(records)
+    }
+
+    fn decode_record(
+        product: GraphicalProduct,
+        location: &str,
+        data: &[u8],
+    ) -> Result<Self, Gdl90Error> {
+        let mut reader = BitReader { data, offset: 10 };
+
+        let report_number = reader.read(14)? as u16;
+        let report_year = reader.read(7)? as u8;
+        reader.skip(4);
+        let overlay_record_id = reader.read(4)? as u8 + 1;
+        let label = match reader.read(1)? {
+            0 => ObjectLabel::Numeric(reader.read(16)? as u16),
+            _ => {
+                let bytes = reader.bytes(9)?;
+                ObjectLabel::Text(decode_dlac(bytes).trim_end().to_owned())
+            }
+        };
+
+        let _element_flag = reader.read(1)?;
+        let qualifier_flag = reader.read(1)? != 0;
+        let _parameter_flag = reader.read(1)?;
+        let object_element = reader.read(5)? as u8;
+        let object_type = reader.read(4)? as u8;
+        let object_status = reader.read(4)? as u8;
+        let object_qualifier = if qualifier_flag {
+            Some(reader.read(24)?)
+        } else {
+            None
+        };
+
+        let applicability = reader.read(2)?;
+        let time_format = reader.read(2)?;
+        let geometry_option = reader.read(4)? as u8;
+        let _overlay_operator = reader.read(2)?;
+        let vertices = reader.read(6)? as usize + 1;
+
+        let start = match applicability {
+            1 | 3 => RecordTime::read(&mut reader, time_format)?,
+            _ => None,
+        };
+        let end = match applicability {
+            2 | 3 => RecordTime::read(&mut reader, time_format)?,
+            _ => None,
+        };
+
+        let geometry = match geometry_option {
+            3 | 4 => Geometry::Polygon {
+                reference: AltitudeReference::from_option(geometry_option, 3),
+                vertices: Vertex::read_all(&mut reader, vertices)?,
+            },
+            7 | 8 => Geometry::Circle {
+                reference: AltitudeReference::from_option(geometry_option, 7),
+                prism: CircularPrism::read(&mut reader)?,
+            },
+            9 | 10 => Geometry::Point {
+                reference: AltitudeReference::from_option(geometry_option, 9),
+                vertex: Vertex::read(&mut reader)?,
+            },
+            11 | 12 => Geometry::Polyline {
+                reference: AltitudeReference::from_option(geometry_option, 11),
+                vertices: Vertex::read_all(&mut reader, vertices)?,
+            },
+            option => Geometry::Unsupported(option),
+        };
+
+        Ok(Self {
+            product,
+            location: location.to_owned(),
+            report_number,
+            report_year,
+            overlay_record_id,
+            label,
+            object_element,
+            object_type,
+            object_status,
+            object_qualifier,
+            start,
+            end,
+            geometry,
+        })
+    }
+}
+
+/// Label of an overlay object.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ObjectLabel {
+    Numeric(u16),
+    Text(String),
+}
+
+/// Start or end time of an overlay record (UTC). Which fields are present depends on the
+/// record Date/Time Format.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct RecordTime {
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+    pub hour: u8,
+    pub minute: u8,
+}
+
+impl RecordTime {
+    /// Reads a time in the given format (0 = none, 1 = month/day/hour/minute,
+    /// 2 = day/hour/minute, 3 = hour/minute), one byte per field.
+    fn read(reader: &mut BitReader, format: u32) -> Result<Option<Self>, Gdl90Error> {
+        let (month, day) = match format {
+            0 => return Ok(None),
+            1 => (Some(reader.read(8)? as u8), Some(reader.read(8)? as u8)),
+            2 => (None, Some(reader.read(8)? as u8)),
+            _ => (None, None),
+        };
+        Ok(Some(Self {
+            month,
+            day,
+            hour: reader.read(8)? as u8,
+            minute: reader.read(8)? as u8,
+        }))
+    }
+}
+
+/// Reference of the altitudes of a geometry.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AltitudeReference {
+    /// Mean Sea Level.
+    Msl,
+    /// Above Ground Level.
+    Agl,
+}
+
+impl AltitudeReference {
+    /// Geometry options come in pairs, the first one being MSL.
+    fn from_option(option: u8, msl: u8) -> Self {
+        if option == msl {
+            Self::Msl
+        } else {
+            Self::Agl
+        }
+    }
+}
+
+/// Geometry of an overlay record.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Geometry {
+    /// Extended Range 3D Polygon.
+    Polygon {
+        reference: AltitudeReference,
+        vertices: Vec<Vertex>,
+    },
+    /// Extended Range 3D Polyline.
+    Polyline {
+        reference: AltitudeReference,
+        vertices: Vec<Vertex>,
+    },
+    /// Extended Range 3D Point.
+    Point {
+        reference: AltitudeReference,
+        vertex: Vertex,
+    },
+    /// Extended Range Circular Prism.
+    Circle {
+        reference: AltitudeReference,
+        prism: CircularPrism,
+    },
+    /// Any other geometry option, which is not decoded.
+    Unsupported(u8),
+}
+
+/// Vertex of a geometry.
+///
+/// | Bits  | Name                      |
+/// |-------|---------------------------|
+/// | 0-18  | Longitude                 |
+/// | 19-37 | Latitude                  |
+/// | 38-47 | Altitude (100 ft. units)  |
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Vertex {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Altitude in feet.
+    pub altitude: i32,
+}
+
+impl Vertex {
+    fn read(reader: &mut BitReader) -> Result<Self, Gdl90Error> {
+        let longitude = reader.read(19)? as f64 * VERTEX_RESOLUTION;
+        let latitude = reader.read(19)? as f64 * VERTEX_RESOLUTION;
+        let altitude = reader.read(10)? as i32 * 100;
+        Ok(Self {
+            latitude: wrap_degrees(latitude),
+            longitude: wrap_degrees(longitude),
+            altitude,
+        })
+    }
+
+    fn read_all(reader: &mut BitReader, count: usize) -> Result<Vec<Self>, Gdl90Error> {
+        (0..count).map(|_| Self::read(reader)).collect()
+    }
+}
+
+/// Circular prism geometry.
+///
+/// | Bits    | Name                          |
+/// |---------|-------------------------------|
+/// | 0-17    | Bottom Longitude              |
+/// | 18-35   | Bottom Latitude               |
+/// | 36-53   | Top Longitude                 |
+/// | 54-71   | Top Latitude                  |
+/// | 72-78   | Bottom Altitude (500 ft.)     |
+/// | 79-85   | Top Altitude (500 ft.)        |
+/// | 86-94   | Longitude Radius (0.2 NM)     |
+/// | 95-103  | Latitude Radius (0.2 NM)      |
+/// | 104-111 | Orientation                   |
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct CircularPrism {
+    pub bottom: Vertex,
+    pub top: Vertex,
+    /// Radius along the longitude axis, in nautical miles.
+    pub radius_longitude: f32,
+    /// Radius along the latitude axis, in nautical miles.
+    pub radius_latitude: f32,
+    /// Orientation of the ellipse, in degrees.
+    pub orientation: f32,
+}
+
+impl CircularPrism {
+    fn read(reader: &mut BitReader) -> Result<Self, Gdl90Error> {
+        let mut center = || -> Result<(f64, f64), Gdl90Error> {
+            let longitude = reader.read(18)? as f64 * CIRCLE_RESOLUTION;
+            let latitude = reader.read(18)? as f64 * CIRCLE_RESOLUTION;
+            Ok((wrap_degrees(latitude), wrap_degrees(longitude)))
+        };
+        let (bottom_latitude, bottom_longitude) = center()?;
+        let (top_latitude, top_longitude) = center()?;
+        let bottom_altitude = reader.read(7)? as i32 * 500;
+        let top_altitude = reader.read(7)? as i32 * 500;
+        Ok(Self {
+            bottom: Vertex {
+                latitude: bottom_latitude,
+                longitude: bottom_longitude,
+                altitude: bottom_altitude,
+            },
+            top: Vertex {
+                latitude: top_latitude,
+                longitude: top_longitude,
+                altitude: top_altitude,
+            },
+            radius_longitude: reader.read(9)? as f32 * 0.2,
+            radius_latitude: reader.read(9)? as f32 * 0.2,
+            orientation: reader.read(8)? as f32 * (360.0 / 256.0),
+        })
+    }
+}
+
+/// Coordinates are two's complement angles, covering the whole circle.
+fn wrap_degrees(degrees: f64) -> f64 {
+    if degrees > 180.0 {
+        degrees - 360.0
+    } else {
+        degrees
+    }
+}
+
+/// Reads consecutive bit fields of a record, failing when reading past its end.
+struct BitReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn read(&mut self, len: usize) -> Result<u32, Gdl90Error> {
+        if self.offset + len > self.data.len() * 8 {
+            return Err(Gdl90Error::Truncated);
+        }
+        let value = read_bits(self.data, self.offset, len);
+        self.offset += len;
+        Ok(value)
+    }
+
+    fn skip(&mut self, len: usize) {
+        self.offset += len;
+    }
+
+    /// Reads `len` bytes, which must be byte aligned.
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], Gdl90Error> {
+        let start = self.offset / 8;
+        let bytes = self
+            .data
+            .get(start..start + len)
+            .ok_or(Gdl90Error::Truncated)?;
+        self.offset += len * 8;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::uplink_data::test_apdu;
+
+    /// Packs `(bit length, value)` fields, MSB first.
+    fn pack(fields: &[(usize, u32)]) -> Vec<u8> {
+        let bits: usize = fields.iter().map(|(len, _)| len).sum();
+        let mut data = vec![0u8; bits.div_ceil(8)];
+        let mut offset = 0;
+        for (len, value) in fields {
+            for i in 0..*len {
+                let pos = offset + i;
+                data[pos / 8] |= (((value >> (len - 1 - i)) & 1) as u8) << (7 - pos % 8);
+            }
+            offset += len;
+        }
+        data
+    }
+
+    fn apdu(product_id: u16, records: &[Vec<u8>]) -> FisbApdu {
+        // record format 8, version 0, location "BOS" (DLAC: 2, 15, 19, 32)
+        let mut payload = pack(&[
+            (4, 8),
+            (4, 0),
+            (4, records.len() as u32),
+            (4, 0),
+            (6, 2),
+            (6, 15),
+            (6, 19),
+            (6, 32),
+            (8, 0),
+        ]);
+        for record in records {
+            payload.extend(record);
+        }
+        test_apdu(product_id, None, &payload)
+    }
+
+    /// Builds a record, `fields` are appended after the record length.
+    fn record(fields: &[(usize, u32)]) -> Vec<u8> {
+        let mut data = pack(&[&[(10, 0)], fields].concat());
+        let len = data.len() as u32;
+        data[0] = (len >> 2) as u8;
+        data[1] |= ((len & 3) << 6) as u8;
+        data
+    }
+
+    fn vertex(latitude: f64, longitude: f64, altitude: u32) -> [(usize, u32); 3] {
+        let raw = |degrees: f64| ((degrees + 360.0) % 360.0 / VERTEX_RESOLUTION).round() as u32;
+        [
+            (19, raw(longitude)),
+            (19, raw(latitude)),
+            (10, altitude / 100),
+        ]
+    }
+
+    #[test]
+    fn polygon_works() {
+        let mut fields = vec![
+            (14, 1234),   // report number
+            (7, 24),      // report year
+            (4, 0),       // reserved
+            (4, 2),       // overlay record id - 1
+            (1, 0),       // numeric label
+            (16, 0x0102), // label
+            (3, 0),       // flags
+            (5, 0),       // element
+            (4, 14),      // type
+            (4, 1),       // status
+            (2, 3),       // start and end times
+            (2, 1),       // month, day, hour, minute
+            (4, 3),       // polygon MSL
+            (2, 0),       // operator
+            (6, 2),       // 3 vertices
+            (8, 6),
+            (8, 1),
+            (8, 12),
+            (8, 30),
+            (8, 6),
+            (8, 2),
+            (8, 18),
+            (8, 0),
+        ];
+        fields.extend(vertex(42.0, -71.0, 0));
+        fields.extend(vertex(42.5, -71.5, 1800));
+        fields.extend(vertex(-33.0, 151.0, 18000));
+
+        let records = GraphicalRecord::decode(&apdu(11, &[record(&fields)])).unwrap();
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.product, GraphicalProduct::Airmet);
+        assert_eq!(record.location, "BOS");
+        assert_eq!(record.report_number, 1234);
+        assert_eq!(record.report_year, 24);
+        assert_eq!(record.overlay_record_id, 3);
+        assert_eq!(record.label, ObjectLabel::Numeric(0x0102));
+        assert_eq!(record.object_type, 14);
+        assert_eq!(record.object_status, 1);
+        assert_eq!(record.object_qualifier, None);
+        assert_eq!(
+            record.start,
+            Some(RecordTime {
+                month: Some(6),
+                day: Some(1),
+                hour: 12,
+                minute: 30,
+            })
+        );
+        assert_eq!(record.end.unwrap().hour, 18);
+
+        let Geometry::Polygon {
+            reference,
+            vertices,
+        } = &record.geometry
+        else {
+            panic!("Expected polygon");
+        };
+        assert_eq!(*reference, AltitudeReference::Msl);
+        assert_eq!(vertices.len(), 3);
+        assert!((vertices[0].latitude - 42.0).abs() < 0.001);
+        assert!((vertices[0].longitude - -71.0).abs() < 0.001);
+        assert_eq!(vertices[1].altitude, 1800);
+        assert!((vertices[2].latitude - -33.0).abs() < 0.001);
+        assert!((vertices[2].longitude - 151.0).abs() < 0.001);
+        assert_eq!(vertices[2].altitude, 18000);
+    }
+
+    #[test]
+    fn circle_and_unsupported_work() {
+        let raw = |degrees: f64| ((degrees + 360.0) % 360.0 / CIRCLE_RESOLUTION).round() as u32;
+        let header = [
+            (14, 1),
+            (7, 24),
+            (4, 0),
+            (4, 0),
+            (1, 0),
+            (16, 7),
+            (3, 0b010), // qualifier present
+            (5, 0),
+            (4, 0),
+            (4, 0),
+            (24, 0xABCDEF),
+            (2, 0), // no times
+            (2, 0),
+        ];
+        let circle = record(
+            &[
+                &header[..],
+                &[
+                    (4, 8), // circle AGL
+                    (2, 0),
+                    (6, 0),
+                    (18, raw(-80.0)),
+                    (18, raw(35.0)),
+                    (18, raw(-80.0)),
+                    (18, raw(35.0)),
+                    (7, 0),
+                    (7, 36),
+                    (9, 15),
+                    (9, 15),
+                    (8, 64),
+                ],
+            ]
+            .concat(),
+        );
+        let unsupported = record(&[&header[..], &[(4, 5), (2, 0), (6, 0)]].concat());
+
+        let records = GraphicalRecord::decode(&apdu(8, &[circle, unsupported])).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].product, GraphicalProduct::NotamTfr);
+        assert_eq!(records[0].object_qualifier, Some(0xABCDEF));
+        assert_eq!(records[0].start, None);
+        assert_eq!(records[0].end, None);
+
+        let Geometry::Circle { reference, prism } = &records[0].geometry else {
+            panic!("Expected circle");
+        };
+        assert_eq!(*reference, AltitudeReference::Agl);
+        assert!((prism.bottom.latitude - 35.0).abs() < 0.002);
+        assert!((prism.bottom.longitude - -80.0).abs() < 0.002);
+        assert_eq!(prism.top.altitude, 18000);
+        assert!((prism.radius_latitude - 3.0).abs() < 0.001);
+        assert_eq!(prism.orientation, 90.0);
+
+        assert_eq!(records[1].geometry, Geometry::Unsupported(5));
+    }
+
+    #[test]
+    fn invalid_products() {
+        assert!(matches!(
+            GraphicalRecord::decode(&apdu(413, &[])),
+            Err(Gdl90Error::InvalidField {
+                field: "product_id",
+                ..
+            })
+        ));
+
+        let mut text = apdu(11, &[]);
+        text.payload[0] = 0x20;
+        assert!(matches!(
+            GraphicalRecord::decode(&text),
+            Err(Gdl90Error::InvalidField {
+                field: "record_format",
+                value: 2
+            })
+        ));
+
+        // record count of 1, without any record
+        let mut truncated = apdu(11, &[]);
+        truncated.payload[1] = 0x10;
+        assert!(matches!(
+            GraphicalRecord::decode(&truncated),
+            Err(Gdl90Error::Truncated)
+        ));
+    }
+}
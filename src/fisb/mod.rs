@@ -0,0 +1,19 @@
+//! FIS-B (Flight Information Services - Broadcast) product decoding.
+//!
+//! FIS-B products are received through [`Gdl90DatalinkMessage::UplinkData`](crate::datalink::Gdl90DatalinkMessage::UplinkData)
+//! messages. Their payload is split into APDUs with [`UplinkPayload::fisb_apdus`](crate::types::uplink_data::UplinkPayload::fisb_apdus),
+//! which are then decoded by the modules below, depending on their product ID.
+//!
+//! | Product ID | Name                  | Module       |
+//! |------------|-----------------------|--------------|
+//! | 8, 11-15   | Graphical Overlays    | [`graphics`] |
+//! | 63         | Regional NEXRAD       | [`nexrad`]   |
+//! | 64         | CONUS NEXRAD          | [`nexrad`]   |
+//! | 413        | Generic Textual Data  | [`text`]     |
+//!
+//! Products split across several APDUs are reassembled by [`assembler::ProductAssembler`].
+
+pub mod assembler;
+pub mod graphics;
+pub mod nexrad;
+pub mod text;
@@ -0,0 +1,484 @@
+//! FIS-B NEXRAD products (63 - Regional, 64 - CONUS). DO-358 - ref 2.2.3.
+//!
+//! NEXRAD images are transmitted as blocks of the Global Block Representation: the globe is
+//! split into rings of 4 arcminutes, each one numbered with 450 blocks of 48 arcminutes. Above
+//! 60 degrees, only the even block numbers are used, for 225 blocks of 96 arcminutes per ring.
+//! Each block holds 4 rows of 32 intensity bins.
+//!
+//! An APDU either holds a single run-length encoded block, or a list of empty blocks.
+//!
+//! ## Example
+//! ```
+//! use core::time::Duration;
+//! use gdl90::fisb::nexrad::{NexradAccumulator, NexradBlock};
+//!
+//! // 128 bins of intensity 3 in a single block
+//! let block = NexradBlock::decode_rle(&[0x80, 0x00, 0x01, 0xFB, 0xFB, 0xFB, 0xFB]).unwrap();
+//! let bounds = block.bounds();
+//!
+//! let mut accumulator = NexradAccumulator::new(Duration::from_secs(600));
+//! accumulator.insert(block, Duration::from_secs(0));
+//! assert_eq!(accumulator.intensity_at(bounds.north - 0.01, bounds.west + 0.01), Some(3));
+//!
+//! // block is dropped after 10 minutes
+//! accumulator.expire(Duration::from_secs(601));
+//! assert_eq!(accumulator.intensity_at(bounds.north - 0.01, bounds.west + 0.01), None);
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use crate::error::Gdl90Error;
+use crate::types::uplink_data::FisbApdu;
+
+/// Product ID of Regional NEXRAD APDUs.
+pub const PRODUCT_ID_NEXRAD_REGIONAL: u16 = 63;
+
+/// Product ID of CONUS NEXRAD APDUs.
+pub const PRODUCT_ID_NEXRAD_CONUS: u16 = 64;
+
+/// Number of intensity bins of a block.
+pub const NEXRAD_BINS: usize = NEXRAD_ROWS * NEXRAD_COLUMNS;
+
+/// Number of bin rows of a block.
+pub const NEXRAD_ROWS: usize = 4;
+
+/// Number of bin columns of a block.
+pub const NEXRAD_COLUMNS: usize = 32;
+
+/// Blocks below this number are located under 60 degrees of latitude.
+const BLOCK_THRESHOLD: u32 = 405000;
+/// Block numbers of a ring.
+const BLOCKS_PER_RING: u32 = 450;
+/// Blocks of a ring above 60 degrees of latitude, using the even block numbers only.
+const WIDE_BLOCKS_PER_RING: u32 = 225;
+
+/// Block height in degrees.
+const BLOCK_HEIGHT: f64 = 4.0 / 60.0;
+/// Block width in degrees, below 60 degrees of latitude.
+const BLOCK_WIDTH: f64 = 48.0 / 60.0;
+/// Block width in degrees, above 60 degrees of latitude.
+const WIDE_BLOCK_WIDTH: f64 = 96.0 / 60.0;
+
+/// NEXRAD block of the Global Block Representation.
+#[derive(Debug, PartialEq, Clone)]
+pub struct NexradBlock {
+    /// Block number, starting at 0 degrees of longitude on the equator.
+    pub block_number: u32,
+
+    /// Whether the block is on the southern hemisphere.
+    pub south: bool,
+
+    /// Block size scale factor (0 = high, 1 = medium (x5), 2 = low (x9) resolution).
+    pub scale_factor: u8,
+
+    /// Intensity bins (0-7), row by row from north-west to south-east.
+    pub bins: [u8; NEXRAD_BINS],
+}
+
+impl NexradBlock {
+    /// Decodes every block of a NEXRAD APDU.
+    ///
+    /// Returns [`Gdl90Error::InvalidField`] if the APDU is not a NEXRAD product.
+    pub fn decode(apdu: &FisbApdu) -> Result<Vec<Self>, Gdl90Error> {
+        match apdu.product_id {
+            PRODUCT_ID_NEXRAD_REGIONAL | PRODUCT_ID_NEXRAD_CONUS => {}
+            id => {
+                return Err(Gdl90Error::InvalidField {
+                    field: "product_id",
+                    value: id as u32,
+                })
+            }
+        }
+
+        match apdu.payload.first() {
+            Some(b) if b & 0x80 != 0 => Ok(vec![Self::decode_rle(&apdu.payload)?]),
+            Some(_) => Self::decode_empty(&apdu.payload),
+            None => Err(Gdl90Error::Truncated),
+        }
+    }
+
+    /// Decodes a run-length encoded block.
+    ///
+    /// | Bits  | Name                  |
+    /// |-------|-----------------------|
+    /// | 0     | Element Identifier    |
+    /// | 1     | North/South           |
+    /// | 2-3   | Scale Factor          |
+    /// | 4-23  | Block Number          |
+    /// | 24-   | Runs (5-bit run length - 1, 3-bit intensity) |
+    pub fn decode_rle(data: &[u8]) -> Result<Self, Gdl90Error> {
+        let (mut block, runs) = Self::decode_header(data)?;
+
+        let mut pos = 0;
+        for run in runs {
+            let length = (run >> 3) as usize + 1;
+            if pos + length > NEXRAD_BINS {
+                return Err(Gdl90Error::InvalidField {
+                    field: "nexrad_bins",
+                    value: (pos + length) as u32,
+                });
+            }
+            block.bins[pos..pos + length].fill(run & 0x07);
+            pos += length;
+        }
+        if pos != NEXRAD_BINS {
+            return Err(Gdl90Error::InvalidField {
+                field: "nexrad_bins",
+                value: pos as u32,
+            });
+        }
+        Ok(block)
+    }
+
+    /// Decodes a list of empty blocks.
+    ///
+    /// The header block is empty, and is followed by a bitmap whose length (in bytes) is held by
+    /// the low nibble of its first byte. Its high nibble and the following bytes flag the empty
+    /// blocks following the header block, on the same ring.
+    pub fn decode_empty(data: &[u8]) -> Result<Vec<Self>, Gdl90Error> {
+        let (block, bitmap) = Self::decode_header(data)?;
+        let Some(first) = bitmap.first() else {
+            return Err(Gdl90Error::Truncated);
+        };
+        let len = (first & 0x0F) as usize;
+        if len > bitmap.len() {
+            return Err(Gdl90Error::Truncated);
+        }
+
+        // above 60 degrees, consecutive blocks are 2 block numbers apart
+        let (ring_size, step) = match block.block_number >= BLOCK_THRESHOLD {
+            true => (WIDE_BLOCKS_PER_RING, 2),
+            false => (BLOCKS_PER_RING, 1),
+        };
+        let ring_start = block.block_number - block.block_number % BLOCKS_PER_RING;
+        let ring_offset = (block.block_number - ring_start) / step;
+
+        let mut blocks = vec![];
+        for (i, bits) in bitmap[..len].iter().enumerate() {
+            // the first byte holds the length, bit 3 stands for the header block
+            let bits = if i == 0 { (bits & 0xF0) | 0x08 } else { *bits };
+            for j in 0..8 {
+                if bits & (1 << j) == 0 || (i == 0 && j < 3) {
+                    continue;
+                }
+                let column = (ring_offset + 8 * i as u32 + j - 3) % ring_size;
+                blocks.push(Self {
+                    block_number: ring_start + column * step,
+                    ..block.clone()
+                });
+            }
+        }
+        Ok(blocks)
+    }
+
+    /// Decodes the 3-byte block header, returning an empty block and the remaining data.
+    fn decode_header(data: &[u8]) -> Result<(Self, &[u8]), Gdl90Error> {
+        if data.len() < 3 {
+            return Err(Gdl90Error::Truncated);
+        }
+        let block = Self {
+            block_number: ((data[0] as u32 & 0x0F) << 16)
+                | ((data[1] as u32) << 8)
+                | data[2] as u32,
+            south: data[0] & 0x40 != 0,
+            scale_factor: (data[0] & 0x30) >> 4,
+            bins: [0; NEXRAD_BINS],
+        };
+        Ok((block, &data[3..]))
+    }
+
+    /// Intensity of the bin at `row` (0 is north) and `column` (0 is west).
+    pub fn intensity(&self, row: usize, column: usize) -> u8 {
+        self.bins[row * NEXRAD_COLUMNS + column]
+    }
+
+    /// Geographic bounds of the block.
+    pub fn bounds(&self) -> BlockBounds {
+        let ring = self.block_number / BLOCKS_PER_RING;
+        let (column, mut width) = if self.block_number >= BLOCK_THRESHOLD {
+            ((self.block_number % BLOCKS_PER_RING) / 2, WIDE_BLOCK_WIDTH)
+        } else {
+            (self.block_number % BLOCKS_PER_RING, BLOCK_WIDTH)
+        };
+
+        let mut height = BLOCK_HEIGHT;
+        let scale = match self.scale_factor {
+            1 => 5.0,
+            2 => 9.0,
+            _ => 1.0,
+        };
+        width *= scale;
+        height *= scale;
+
+        let north = if self.south {
+            -(ring as f64 * BLOCK_HEIGHT)
+        } else {
+            (ring + 1) as f64 * BLOCK_HEIGHT
+        };
+        let mut west = column as f64 * width / scale;
+        if west >= 180.0 {
+            west -= 360.0;
+        }
+
+        BlockBounds {
+            north,
+            west,
+            height,
+            width,
+        }
+    }
+}
+
+/// Geographic bounds of a [`NexradBlock`], in degrees.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct BlockBounds {
+    /// Latitude of the northern edge.
+    pub north: f64,
+    /// Longitude of the western edge.
+    pub west: f64,
+    /// Height of the block.
+    pub height: f64,
+    /// Width of the block.
+    pub width: f64,
+}
+
+impl BlockBounds {
+    /// Whether the position is within the bounds.
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        let mut dlon = longitude - self.west;
+        if dlon < 0.0 {
+            dlon += 360.0;
+        }
+        latitude <= self.north && latitude > self.north - self.height && dlon < self.width
+    }
+}
+
+/// Merges NEXRAD blocks received through successive uplinks into a single layer.
+///
+/// Blocks are replaced when received again, and dropped when older than the maximum age.
+/// Times are provided by the caller (eg. elapsed time since startup).
+#[derive(Debug, Clone)]
+pub struct NexradAccumulator {
+    max_age: Duration,
+    updated: Option<Duration>,
+    blocks: BTreeMap<(u32, bool, u8), (NexradBlock, Duration)>,
+}
+
+impl NexradAccumulator {
+    /// Creates an empty accumulator, dropping blocks older than `max_age`.
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            max_age,
+            updated: None,
+            blocks: BTreeMap::new(),
+        }
+    }
+
+    /// Adds (or replaces) a block received at `received`.
+    pub fn insert(&mut self, block: NexradBlock, received: Duration) {
+        let key = (block.block_number, block.south, block.scale_factor);
+        self.blocks.insert(key, (block, received));
+        self.updated = self.updated.max(Some(received));
+    }
+
+    /// Decodes a NEXRAD APDU received at `received` and adds its blocks.
+    ///
+    /// Returns the number of blocks added.
+    pub fn insert_apdu(
+        &mut self,
+        apdu: &FisbApdu,
+        received: Duration,
+    ) -> Result<usize, Gdl90Error> {
+        let blocks = NexradBlock::decode(apdu)?;
+        let count = blocks.len();
+        for block in blocks {
+            self.insert(block, received);
+        }
+        Ok(count)
+    }
+
+    /// Drops the blocks older than the maximum age at `now`.
+    pub fn expire(&mut self, now: Duration) {
+        let max_age = self.max_age;
+        self.blocks
+            .retain(|_, (_, received)| now.saturating_sub(*received) <= max_age);
+    }
+
+    /// Time of the most recent block, if any was received.
+    pub fn updated(&self) -> Option<Duration> {
+        self.updated
+    }
+
+    /// Number of blocks in the layer.
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Whether the layer has no blocks.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Iterates over the blocks of the layer, with the time they were received at.
+    pub fn blocks(&self) -> impl Iterator<Item = (&NexradBlock, Duration)> {
+        self.blocks
+            .values()
+            .map(|(block, received)| (block, *received))
+    }
+
+    /// Intensity at the given position, using the highest resolution block available.
+    pub fn intensity_at(&self, latitude: f64, longitude: f64) -> Option<u8> {
+        self.blocks()
+            .map(|(block, _)| (block, block.bounds()))
+            .filter(|(_, bounds)| bounds.contains(latitude, longitude))
+            .min_by_key(|(block, _)| block.scale_factor)
+            .map(|(block, bounds)| {
+                let mut dlon = longitude - bounds.west;
+                if dlon < 0.0 {
+                    dlon += 360.0;
+                }
+                let row = ((bounds.north - latitude) / bounds.height * NEXRAD_ROWS as f64) as usize;
+                let column = (dlon / bounds.width * NEXRAD_COLUMNS as f64) as usize;
+                block.intensity(row.min(NEXRAD_ROWS - 1), column.min(NEXRAD_COLUMNS - 1))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::uplink_data::test_apdu;
+
+    #[test]
+    fn rle_block_works() {
+        // block 283500 (42N), 32 bins of 0, 64 bins of 5, 32 bins of 0
+        let data = vec![0x84, 0x53, 0x6C, 0xF8, 0xFD, 0xFD, 0xF8];
+        let blocks = NexradBlock::decode(&test_apdu(64, None, &data)).unwrap();
+        assert_eq!(blocks.len(), 1);
+        let block = &blocks[0];
+        assert_eq!(block.block_number, 283500);
+        assert!(!block.south);
+        assert_eq!(block.scale_factor, 0);
+        assert_eq!(block.intensity(0, 31), 0);
+        assert_eq!(block.intensity(1, 0), 5);
+        assert_eq!(block.intensity(2, 31), 5);
+        assert_eq!(block.intensity(3, 0), 0);
+
+        let bounds = block.bounds();
+        assert!((bounds.north - (631.0 * 4.0 / 60.0)).abs() < 1e-9);
+        assert_eq!(bounds.west, 0.0);
+        assert!((bounds.width - 0.8).abs() < 1e-9);
+
+        // not enough bins
+        assert!(matches!(
+            NexradBlock::decode_rle(&[0x80, 0x00, 0x01, 0x7B]),
+            Err(Gdl90Error::InvalidField { .. })
+        ));
+        assert!(matches!(
+            NexradBlock::decode(&test_apdu(413, None, &[])),
+            Err(Gdl90Error::InvalidField { .. })
+        ));
+    }
+
+    #[test]
+    fn block_bounds_work() {
+        let block = |block_number, south, scale_factor| NexradBlock {
+            block_number,
+            south,
+            scale_factor,
+            bins: [0; NEXRAD_BINS],
+        };
+
+        // west of greenwich
+        let bounds = block(449, false, 0).bounds();
+        assert!((bounds.west - -0.8).abs() < 1e-9);
+        assert!((bounds.north - 4.0 / 60.0).abs() < 1e-9);
+
+        // southern hemisphere, medium resolution
+        let bounds = block(450, true, 1).bounds();
+        assert!((bounds.north - -4.0 / 60.0).abs() < 1e-9);
+        assert!((bounds.height - 20.0 / 60.0).abs() < 1e-9);
+        assert!((bounds.width - 4.0).abs() < 1e-9);
+
+        // above 60 degrees, only even block numbers are used
+        let bounds = block(BLOCK_THRESHOLD + 226, false, 0).bounds();
+        assert!((bounds.north - 901.0 * 4.0 / 60.0).abs() < 1e-9);
+        assert!((bounds.west - -179.2).abs() < 1e-9);
+        assert!((bounds.width - 1.6).abs() < 1e-9);
+
+        let bounds = block(BLOCK_THRESHOLD + 452, false, 0).bounds();
+        assert!((bounds.north - 902.0 * 4.0 / 60.0).abs() < 1e-9);
+        assert!((bounds.west - 1.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn empty_blocks_work() {
+        // block 10, 2 bitmap bytes: blocks 11, 12 and 14 (bits 4, 5 and 7),
+        // then 15 (bit 0 of the second byte)
+        let blocks = NexradBlock::decode_empty(&[0x00, 0x00, 0x0A, 0xB2, 0x01]).unwrap();
+        let numbers: Vec<_> = blocks.iter().map(|b| b.block_number).collect();
+        assert_eq!(numbers, [10, 11, 12, 14, 15]);
+        assert!(blocks.iter().all(|b| b.bins == [0; NEXRAD_BINS]));
+
+        // wraps around the ring
+        let blocks = NexradBlock::decode_empty(&[0x00, 0x01, 0xC1, 0x11]).unwrap();
+        let numbers: Vec<_> = blocks.iter().map(|b| b.block_number).collect();
+        assert_eq!(numbers, [449, 0]);
+
+        // above 60 degrees, blocks are 2 block numbers apart
+        let blocks = NexradBlock::decode_empty(&[0x06, 0x2E, 0x0A, 0x51]).unwrap();
+        let numbers: Vec<_> = blocks.iter().map(|b| b.block_number).collect();
+        assert_eq!(numbers, [405002, 405004, 405008]);
+
+        // and wrap around the ring at its last even block number
+        let blocks = NexradBlock::decode_empty(&[0x06, 0x2F, 0xC8, 0x11]).unwrap();
+        let numbers: Vec<_> = blocks.iter().map(|b| b.block_number).collect();
+        assert_eq!(numbers, [405448, 405000]);
+    }
+
+    #[test]
+    fn accumulator_works() {
+        let mut accumulator = NexradAccumulator::new(Duration::from_secs(60));
+        assert!(accumulator.is_empty());
+
+        let rle = test_apdu(64, None, &[0x80, 0x00, 0x01, 0xFB, 0xFB, 0xFB, 0xFB]);
+        assert_eq!(
+            accumulator
+                .insert_apdu(&rle, Duration::from_secs(10))
+                .unwrap(),
+            1
+        );
+        let empty = test_apdu(64, None, &[0x00, 0x00, 0x02, 0x01]);
+        assert_eq!(
+            accumulator
+                .insert_apdu(&empty, Duration::from_secs(50))
+                .unwrap(),
+            1
+        );
+        assert_eq!(accumulator.len(), 2);
+        assert_eq!(accumulator.updated(), Some(Duration::from_secs(50)));
+
+        let lat = 4.0 / 60.0 - 0.001;
+        assert_eq!(accumulator.intensity_at(lat, 0.81), Some(3));
+        assert_eq!(accumulator.intensity_at(lat, 1.61), Some(0));
+        assert_eq!(accumulator.intensity_at(lat, 2.41), None);
+
+        // same block received again, with new data
+        accumulator.insert(
+            NexradBlock {
+                bins: [7; NEXRAD_BINS],
+                ..NexradBlock::decode_rle(&rle.payload).unwrap()
+            },
+            Duration::from_secs(20),
+        );
+        assert_eq!(accumulator.len(), 2);
+        assert_eq!(accumulator.intensity_at(lat, 0.81), Some(7));
+
+        accumulator.expire(Duration::from_secs(100));
+        assert_eq!(accumulator.len(), 1);
+        assert_eq!(accumulator.intensity_at(lat, 0.81), None);
+    }
+}
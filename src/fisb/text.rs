@@ -0,0 +1,329 @@
+//! FIS-B Generic Textual Data products (METAR, TAF, PIREP, NOTAM, WINDS...). DO-358 - ref 2.2.5.
+//!
+//! The text is DLAC encoded (6 bits per character) and holds one or more records
+//! separated by the Record Separator character. Each record has the following layout:
+//!
+//! `<TYPE> <LOCATION> <DDHHMMZ> <BODY>`
+//!
+//! ## Example
+//! ```
+//! use gdl90::fisb::text::{TextReport, TextReportKind};
+//!
+//! let report: TextReport = "METAR KBOS 121854Z 27015KT 10SM FEW250 M02/M17 A3012".parse().unwrap();
+//! assert_eq!(report.kind, TextReportKind::Metar);
+//! assert_eq!(report.location, "KBOS");
+//! assert_eq!(report.issued.unwrap().hour, 18);
+//! assert_eq!(report.body, "27015KT 10SM FEW250 M02/M17 A3012");
+//! ```
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+use crate::error::Gdl90Error;
+use crate::types::uplink_data::FisbApdu;
+
+/// Product ID of Generic Textual Data APDUs.
+pub const PRODUCT_ID_GENERIC_TEXT: u16 = 413;
+
+/// DLAC alphabet, indexed by the 6-bit character code.
+const DLAC_ALPHABET: &[u8; 64] =
+    b"\x03ABCDEFGHIJKLMNOPQRSTUVWXYZ\x1A\t\x1E\n| !\"#$%&'()*+,-./0123456789:;<=>?";
+
+/// DLAC End of Text character.
+const DLAC_ETX: char = '\x03';
+
+/// DLAC Record Separator character.
+const DLAC_RS: char = '\x1E';
+
+/// DLAC code of the tab character, the following character holds the number of spaces.
+const DLAC_TAB: u8 = 28;
+
+/// Decodes DLAC (6-bit) encoded text.
+///
+/// Tabs are expanded to spaces. Control characters (End of Text, Record Separator...) are kept.
+pub fn decode_dlac(data: &[u8]) -> String {
+    let mut text = String::with_capacity(data.len() * 4 / 3);
+    let mut tab = false;
+    for i in 0..data.len() * 8 / 6 {
+        let bit = i * 6;
+        let word = ((data[bit / 8] as u16) << 8) | *data.get(bit / 8 + 1).unwrap_or(&0) as u16;
+        let code = ((word >> (10 - bit % 8)) & 0x3F) as u8;
+        if tab {
+            text.extend(core::iter::repeat_n(' ', code as usize));
+            tab = false;
+        } else if code == DLAC_TAB {
+            tab = true;
+        } else {
+            text.push(DLAC_ALPHABET[code as usize] as char);
+        }
+    }
+    text
+}
+
+/// Decodes every text report of a Generic Textual Data APDU.
+///
+/// Returns [`Gdl90Error::InvalidField`] if the APDU is not a [`PRODUCT_ID_GENERIC_TEXT`] product.
+/// Records which can not be parsed (eg. empty ones) are skipped.
+pub fn decode_text_reports(apdu: &FisbApdu) -> Result<Vec<TextReport>, Gdl90Error> {
+    if apdu.product_id != PRODUCT_ID_GENERIC_TEXT {
+        return Err(Gdl90Error::InvalidField {
+            field: "product_id",
+            value: apdu.product_id as u32,
+        });
+    }
+
+    let text = decode_dlac(&apdu.payload);
+    let text = text.split(DLAC_ETX).next().unwrap_or_default();
+    Ok(text
+        .split(DLAC_RS)
+        .filter_map(|record| record.parse().ok())
+        .collect())
+}
+
+/// Type of a text report.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TextReportKind {
+    Metar,
+    Speci,
+    Taf,
+    Pirep,
+    /// NOTAM, including NOTAM-D, NOTAM-FDC and NOTAM-TFR.
+    Notam,
+    Winds,
+    /// Any other report type, holding its name.
+    Other(String),
+}
+
+impl From<&str> for TextReportKind {
+    fn from(value: &str) -> Self {
+        match value {
+            "METAR" => Self::Metar,
+            "SPECI" => Self::Speci,
+            // amended TAFs are sent as "TAF.AMD"
+            "TAF" | "TAF.AMD" => Self::Taf,
+            "PIREP" => Self::Pirep,
+            "NOTAM" | "NOTAM-D" | "NOTAM-FDC" | "NOTAM-TFR" => Self::Notam,
+            "WINDS" => Self::Winds,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+}
+
+/// Time a report was issued at (UTC).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct IssueTime {
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+}
+
+impl FromStr for IssueTime {
+    type Err = Gdl90Error;
+
+    /// Parses a `DDHHMMZ` time.
+    ///
+    /// Fails with [`Gdl90Error::InvalidField`] for `issue_time` (holding the text length) if it
+    /// is not formatted as such, or for `day`, `hour` or `minute` if one is out of range.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s
+            .strip_suffix('Z')
+            .filter(|digits| digits.len() == 6 && digits.bytes().all(|b| b.is_ascii_digit()))
+            .ok_or(Gdl90Error::InvalidField {
+                field: "issue_time",
+                value: s.len() as u32,
+            })?;
+        let field = |i: usize, field: &'static str, max: u8| {
+            let value = (digits.as_bytes()[i] - b'0') * 10 + digits.as_bytes()[i + 1] - b'0';
+            match value <= max {
+                true => Ok(value),
+                false => Err(Gdl90Error::InvalidField {
+                    field,
+                    value: value as u32,
+                }),
+            }
+        };
+        Ok(Self {
+            day: field(0, "day", 31)?,
+            hour: field(2, "hour", 23)?,
+            minute: field(4, "minute", 59)?,
+        })
+    }
+}
+
+/// Text report of a Generic Textual Data product.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TextReport {
+    /// Report type.
+    pub kind: TextReportKind,
+
+    /// Location identifier, eg. `KBOS`.
+    pub location: String,
+
+    /// Issue time, if present.
+    pub issued: Option<IssueTime>,
+
+    /// Report text, without its type, location and issue time.
+    pub body: String,
+}
+
+impl FromStr for TextReport {
+    type Err = Gdl90Error;
+
+    /// Parses a single (decoded) text record.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim_matches(|c: char| c.is_whitespace() || c.is_ascii_control());
+        let (kind, rest) = split_token(s);
+        if kind.is_empty() {
+            return Err(Gdl90Error::Truncated);
+        }
+        let (location, rest) = split_token(rest);
+
+        let (time, after_time) = split_token(rest);
+        let (issued, body) = match time.parse() {
+            Ok(issued) => (Some(issued), after_time),
+            Err(_) => (None, rest),
+        };
+
+        Ok(Self {
+            kind: kind.into(),
+            location: location.to_owned(),
+            issued,
+            body: body.trim_end().to_owned(),
+        })
+    }
+}
+
+/// Splits `s` at its first whitespace, returning the first token and the remaining text.
+fn split_token(s: &str) -> (&str, &str) {
+    let s = s.trim_start();
+    match s.find(char::is_whitespace) {
+        Some(pos) => (&s[..pos], s[pos..].trim_start()),
+        None => (s, ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::uplink_data::test_apdu;
+
+    fn encode_dlac(text: &str) -> Vec<u8> {
+        let mut bits = vec![];
+        for c in text.bytes() {
+            let code = DLAC_ALPHABET.iter().position(|&x| x == c).unwrap() as u8;
+            bits.extend((0..6).rev().map(|i| (code >> i) & 1));
+        }
+        bits.chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0, |acc, (i, bit)| acc | (bit << (7 - i)))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn dlac_works() {
+        assert_eq!(decode_dlac(&encode_dlac("KBOS 27015KT")), "KBOS 27015KT");
+        // tab followed by the number of spaces (3 = 'C')
+        assert_eq!(decode_dlac(&encode_dlac("A\tCB")), "A   B");
+        assert_eq!(decode_dlac(&[]), "");
+    }
+
+    #[test]
+    fn text_reports_work() {
+        let text = "METAR KBOS 121854Z 27015KT 10SM FEW250\x1E\
+                    TAF.AMD KJFK 121720Z 1218/1324 VRB03KT P6SM SKC\x1E\
+                    PIREP BNA 121830Z UA /OV BNA/TM 1830/FL050\x1E\
+                    WINDS ATL 121200Z  FT 3000 6000\x1E\
+                    NOTAM-D BOS\x1E\x03";
+        let reports = decode_text_reports(&test_apdu(413, None, &encode_dlac(text))).unwrap();
+        assert_eq!(reports.len(), 5);
+
+        assert_eq!(
+            reports[0],
+            TextReport {
+                kind: TextReportKind::Metar,
+                location: "KBOS".to_owned(),
+                issued: Some(IssueTime {
+                    day: 12,
+                    hour: 18,
+                    minute: 54,
+                }),
+                body: "27015KT 10SM FEW250".to_owned(),
+            }
+        );
+        assert_eq!(reports[1].kind, TextReportKind::Taf);
+        assert_eq!(reports[1].body, "1218/1324 VRB03KT P6SM SKC");
+        assert_eq!(reports[2].kind, TextReportKind::Pirep);
+        assert_eq!(reports[2].location, "BNA");
+        assert_eq!(reports[3].kind, TextReportKind::Winds);
+        assert_eq!(reports[3].body, "FT 3000 6000");
+        assert_eq!(reports[4].kind, TextReportKind::Notam);
+        assert_eq!(reports[4].issued, None);
+        assert_eq!(reports[4].body, "");
+
+        assert!(matches!(
+            decode_text_reports(&test_apdu(63, None, &[])),
+            Err(Gdl90Error::InvalidField {
+                field: "product_id",
+                value: 63
+            })
+        ));
+    }
+
+    #[test]
+    fn report_kinds_work() {
+        for kind in ["NOTAM", "NOTAM-D", "NOTAM-FDC", "NOTAM-TFR"] {
+            assert_eq!(TextReportKind::from(kind), TextReportKind::Notam);
+        }
+        assert_eq!(TextReportKind::from("TAF.AMD"), TextReportKind::Taf);
+        assert_eq!(
+            TextReportKind::from("AIRMET"),
+            TextReportKind::Other("AIRMET".to_owned())
+        );
+    }
+
+    #[test]
+    fn issue_time_works() {
+        assert_eq!(
+            "312359Z".parse::<IssueTime>().unwrap(),
+            IssueTime {
+                day: 31,
+                hour: 23,
+                minute: 59,
+            }
+        );
+        assert!(matches!(
+            "121854".parse::<IssueTime>(),
+            Err(Gdl90Error::InvalidField {
+                field: "issue_time",
+                value: 6
+            })
+        ));
+        assert!(matches!(
+            "12185AZ".parse::<IssueTime>(),
+            Err(Gdl90Error::InvalidField {
+                field: "issue_time",
+                ..
+            })
+        ));
+        assert!(matches!(
+            "122454Z".parse::<IssueTime>(),
+            Err(Gdl90Error::InvalidField {
+                field: "hour",
+                value: 24
+            })
+        ));
+        assert!(matches!(
+            "121860Z".parse::<IssueTime>(),
+            Err(Gdl90Error::InvalidField {
+                field: "minute",
+                value: 60
+            })
+        ));
+    }
+}
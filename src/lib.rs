@@ -11,7 +11,7 @@
 //!
 //! # Usage
 //! ```
-//! use std::io::Cursor;
+//! use binrw::io::Cursor;
 //! use gdl90::datalink::Gdl90DatalinkMessage;
 //! use gdl90::Gdl90Message;
 //!
@@ -19,33 +19,64 @@
 //! let parsed = gdl90::read_raw(&[0x7E, 0x00, 0x81, 0x41, 0xDB, 0xD0, 0x08, 0x02, 0xB3, 0x8B, 0x7E]).unwrap();
 //! assert_eq!(parsed.frame_check_seq, 0x8bb3);
 //! match parsed.message_data {
-//!     Gdl90DatalinkMessage::Heartbeat { status_byte_1, status_byte_2, uat_timestamp, message_counts } => { /*...*/} ,
+//!     Gdl90DatalinkMessage::Heartbeat { heartbeat } => { /*...*/} ,
 //!     Gdl90DatalinkMessage::Initialization { configuration_byte_1, configuration_byte_2 } => { /*...*/},
 //!     Gdl90DatalinkMessage::UplinkData { time_of_reception, payload } => { /*...*/},
 //!     Gdl90DatalinkMessage::HeightAboveTerrain { hat } => { /*...*/},
 //!     Gdl90DatalinkMessage::OwnshipReport { report } => { /*...*/},
 //!     Gdl90DatalinkMessage::TrafficReport { report } => { /*...*/},
 //!     Gdl90DatalinkMessage::OwnshipGeoometricAltitude { ownship_geo_altitude, vertical_metrics } => { /*...*/},
-//!     Gdl90DatalinkMessage::BasicReport() => { /*...*/},
-//!     Gdl90DatalinkMessage::LongReport() => { /*...*/},
-//!     Gdl90DatalinkMessage::Unknown => { /*...*/},
+//!     Gdl90DatalinkMessage::BasicReport { .. } => { /*...*/},
+//!     Gdl90DatalinkMessage::LongReport { .. } => { /*...*/},
+//!     Gdl90DatalinkMessage::ForeFlight { message } => { /*...*/},
+//!     #[cfg(feature = "stratux")]
+//!     Gdl90DatalinkMessage::StratuxAhrs { .. }
+//!     | Gdl90DatalinkMessage::StratuxStatus { .. }
+//!     | Gdl90DatalinkMessage::StratuxHeartbeat { .. } => { /*...*/},
+//!     Gdl90DatalinkMessage::Unknown { id, payload } => { /*...*/},
 //! }
 //! ```
 //!
-//! See [`Gdl90Message`] for more usage details.
+//! See [`Gdl90Message`] for more usage details, [`decoder::Gdl90Decoder`] for decoding
+//! messages from a stream of bytes, [`borrowed::Gdl90MessageRef`] for decoding without copying
+//! the message data, and [`vendor`] for decoding vendor messages.
+//!
+//! # Features
+//!
+//! - `std` (default): without it, the crate is `no_std` and only requires `alloc`. Every message
+//!   is still decoded from and encoded to byte slices, see [`read_raw`], [`frame_into`] and
+//!   [`write_into`].
+//! - `stratux`: Stratux vendor messages, see [`datalink`].
 //!
 //! Note: Work in progress, feel free to contribute.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+// tests use the std macros (eg. `vec!`) in both modes
+#[cfg(all(test, not(feature = "std")))]
+#[macro_use]
+extern crate std;
+
+pub mod borrowed;
 pub mod control;
 pub mod crc;
 pub mod datalink;
+pub mod decoder;
+pub mod error;
+pub mod fisb;
 pub mod types;
+pub mod vendor;
 
-use std::io::Cursor;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
 
-use binrw::{binread, BinRead};
+use binrw::io::{Cursor, SeekFrom};
+use binrw::{binrw, BinRead, BinWrite};
 use crc::gdl90_crc;
 use datalink::Gdl90DatalinkMessage;
+use error::Gdl90Error;
 
 pub const GDL90_ESCAPEBYTE: u8 = 0x7D;
 pub const GDL90_MAGIC: u8 = 0x7E;
@@ -57,42 +88,87 @@ pub const GDL90_MAGIC: u8 = 0x7E;
 /// ```
 /// use gdl90::Gdl90Message;
 /// use binrw::BinRead;
-/// use std::io::Cursor;
+/// use binrw::io::Cursor;
 ///
 /// let mut data = Cursor::new(b"\x7E\x00\x81\x41\xDB\xD0\x08\x02\xB3\x8B\x7E");
 /// let parsed = Gdl90Message::read(&mut data).unwrap();
 /// // unwrap fails if the specified data is not 100% correct
 /// ```
 ///
+/// Decoding failures are reported as [`binrw::Error::Custom`] holding a [`Gdl90Error`],
+/// which is converted when using [`read_raw`].
+///
 /// If you dont want to use the trait, you can use the aux function [`read_raw`]
 /// ```
 /// let parsed = gdl90::read_raw(&[0x7E, 0x00, 0x81, 0x41, 0xDB, 0xD0, 0x08, 0x02, 0xB3, 0x8B, 0x7E]);
 /// ```
-#[binread]
-#[derive(Debug)]
-#[br(little, magic = b"\x7E")]
+///
+/// It also implements [`BinWrite`], producing a complete frame (flags, escaped data and CRC).
+/// The frame check sequence is always recalculated when writing:
+/// ```
+/// use gdl90::Gdl90Message;
+///
+/// let parsed = gdl90::read_raw(&[0x7E, 0x00, 0x81, 0x41, 0xDB, 0xD0, 0x08, 0x02, 0xB3, 0x8B, 0x7E]).unwrap();
+/// let bytes = gdl90::write_raw(&Gdl90Message::new(parsed.message_data)).unwrap();
+/// assert_eq!(bytes, [0x7E, 0x00, 0x81, 0x41, 0xDB, 0xD0, 0x08, 0x02, 0xB3, 0x8B, 0x7E]);
+/// ```
+#[binrw]
+#[derive(Debug, PartialEq)]
+#[brw(little)]
+#[br(magic = b"\x7E")]
 pub struct Gdl90Message {
     #[br(temp, parse_with = parse_message_bytes)]
+    #[bw(ignore)]
     data: Vec<u8>,
 
     /// Message payload depending on message id.
-    #[br(map_stream = |_| Cursor::new(&data))]
+    #[br(map_stream = |_| Cursor::new(&data[..data.len() - 2]), parse_with = parse_message_data)]
+    #[bw(write_with = write_message_bytes)]
     pub message_data: Gdl90DatalinkMessage,
 
-    /// Frame Check Sequence. If not valid, assertion fails.
-    #[br(assert(frame_check_seq == gdl90_crc(&data), "bad checksum of {:02X?}: {:#x?} != {:#x?}", data, frame_check_seq, gdl90_crc(&data)))]
+    /// Frame Check Sequence. Already validated by [`read_raw`].
+    #[br(calc = u16::from_le_bytes([data[data.len() - 2], data[data.len() - 1]]))]
+    #[bw(ignore)]
     pub frame_check_seq: u16,
 }
 
+impl Gdl90Message {
+    /// Creates a new message from `message_data`, calculating its frame check sequence.
+    pub fn new(message_data: Gdl90DatalinkMessage) -> Self {
+        let mut data = Cursor::new(vec![]);
+        // writing to an in-memory buffer does not fail
+        let _ = message_data.write(&mut data);
+        let frame_check_seq = gdl90_crc(data.get_ref());
+        Self {
+            message_data,
+            frame_check_seq,
+        }
+    }
+}
+
 /// Reads from a raw buffer. Internally, it creates a `Cursor` and uses `BinRead` trait.
-pub fn read_raw(buffer: &[u8]) -> Result<Gdl90Message, String> {
-    Gdl90Message::read(&mut Cursor::new(buffer)).map_err(|err| format!("{err:?}").to_string())
+pub fn read_raw(buffer: &[u8]) -> Result<Gdl90Message, Gdl90Error> {
+    Ok(Gdl90Message::read(&mut Cursor::new(buffer))?)
+}
+
+/// Writes `message` to a raw buffer. Internally, it creates a `Cursor` and uses `BinWrite` trait.
+pub fn write_raw(message: &Gdl90Message) -> Result<Vec<u8>, Gdl90Error> {
+    let mut output = Cursor::new(vec![]);
+    message.write(&mut output)?;
+    Ok(output.into_inner())
 }
 
 /// 2.2.1. - Look for all Control-Escape characters in the saved string. Discard each one found, and XOR the
 /// following character with 0x20.
-fn remove_escapes(data: Vec<u8>) -> Vec<u8> {
-    let mut result = Vec::new();
+fn remove_escapes(mut data: Vec<u8>) -> Vec<u8> {
+    let len = remove_escapes_in_place(&mut data);
+    data.truncate(len);
+    data
+}
+
+/// Same as [`remove_escapes`], but unescapes `data` in place and returns its unescaped length.
+pub(crate) fn remove_escapes_in_place(data: &mut [u8]) -> usize {
+    let mut len = 0;
     let mut i = 0;
     while i < data.len() {
         let byte = data[i];
@@ -101,36 +177,221 @@ fn remove_escapes(data: Vec<u8>) -> Vec<u8> {
                 i += 1;
                 let escaped_byte = data[i] ^ 0x20;
                 // FIXME ??? if escaped_byte == 0x7D || escaped_byte == 0x7E
-                result.push(escaped_byte);
+                data[len] = escaped_byte;
+                len += 1;
             }
         } else {
-            result.push(byte);
+            data[len] = byte;
+            len += 1;
         }
         i += 1;
     }
+    len
+}
+
+/// 2.2.1. - Any Control-Escape or Flag character found in the message data or CRC is escaped by
+/// inserting a Control-Escape character and XORing the original character with 0x20.
+///
+/// ```
+/// assert_eq!(gdl90::add_escapes(&[0x01, 0x7E, 0x7D]), [0x01, 0x7D, 0x5E, 0x7D, 0x5D]);
+/// ```
+pub fn add_escapes(data: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(data.len());
+    for &byte in data {
+        if byte == GDL90_ESCAPEBYTE || byte == GDL90_MAGIC {
+            result.push(GDL90_ESCAPEBYTE);
+            result.push(byte ^ 0x20);
+        } else {
+            result.push(byte);
+        }
+    }
     result
 }
 
+/// Builds a complete GDL90 frame: the [`GDL90_MAGIC`] flags surrounding `message_id`, `payload`
+/// and their CRC (calculated with [`gdl90_crc`], LSB first), escaped using [`add_escapes`].
+///
+/// ```
+/// let frame = gdl90::frame(0x00, &[0x81, 0x41, 0xDB, 0xD0, 0x08, 0x02]);
+/// assert_eq!(frame, [0x7E, 0x00, 0x81, 0x41, 0xDB, 0xD0, 0x08, 0x02, 0xB3, 0x8B, 0x7E]);
+/// ```
+pub fn frame(message_id: u8, payload: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(payload.len() + 3);
+    data.push(message_id);
+    data.extend_from_slice(payload);
+    frame_data(data)
+}
+
+/// Same as [`frame`], but writes the frame to the start of `buffer` and returns its length,
+/// without allocating.
+///
+/// Fails with [`Gdl90Error::BufferTooSmall`] if `buffer` cannot hold the escaped frame.
+///
+/// ```
+/// let mut buffer = [0; gdl90::decoder::GDL90_MAX_FRAME_LEN];
+/// let len = gdl90::frame_into(0x00, &[0x81, 0x41, 0xDB, 0xD0, 0x08, 0x02], &mut buffer).unwrap();
+/// assert_eq!(buffer[..len], [0x7E, 0x00, 0x81, 0x41, 0xDB, 0xD0, 0x08, 0x02, 0xB3, 0x8B, 0x7E]);
+/// ```
+pub fn frame_into(message_id: u8, payload: &[u8], buffer: &mut [u8]) -> Result<usize, Gdl90Error> {
+    let data = buffer
+        .get_mut(1..payload.len() + 2)
+        .ok_or(Gdl90Error::BufferTooSmall)?;
+    data[0] = message_id;
+    data[1..].copy_from_slice(payload);
+    frame_in_place(buffer, payload.len() + 1)
+}
+
+/// Same as [`write_raw`], but writes the frame to the start of `buffer` and returns its length.
+///
+/// Fails with [`Gdl90Error::BufferTooSmall`] if `buffer` cannot hold the escaped frame, which
+/// never happens with [`decoder::GDL90_MAX_FRAME_LEN`] bytes for the messages defined by the
+/// specification.
+pub fn write_into(message: &Gdl90Message, buffer: &mut [u8]) -> Result<usize, Gdl90Error> {
+    let data = buffer.get_mut(1..).ok_or(Gdl90Error::BufferTooSmall)?;
+    let mut output = Cursor::new(data);
+    message
+        .message_data
+        .write_options(&mut output, binrw::Endian::Little, ())
+        .map_err(|err| match err {
+            binrw::Error::Io(err) if err.kind() == binrw::io::ErrorKind::WriteZero => {
+                Gdl90Error::BufferTooSmall
+            }
+            err => Gdl90Error::from(err),
+        })?;
+    let len = output.position() as usize;
+    frame_in_place(buffer, len)
+}
+
+/// Completes the frame whose unescaped message id and data are in `buffer[1..len + 1]`:
+/// appends their CRC, escapes them in place and adds the flags. Returns the frame length.
+fn frame_in_place(buffer: &mut [u8], len: usize) -> Result<usize, Gdl90Error> {
+    let crc = gdl90_crc(&buffer[1..len + 1]);
+    let end = len + 3;
+    buffer
+        .get_mut(len + 1..end)
+        .ok_or(Gdl90Error::BufferTooSmall)?
+        .copy_from_slice(&crc.to_le_bytes());
+
+    let escapes = buffer[1..end]
+        .iter()
+        .filter(|&&b| b == GDL90_ESCAPEBYTE || b == GDL90_MAGIC)
+        .count();
+    let frame_len = end + escapes + 1;
+    if buffer.len() < frame_len {
+        return Err(Gdl90Error::BufferTooSmall);
+    }
+
+    // escaped from the end, so that every byte is moved before being overwritten
+    let mut dest = end + escapes;
+    for src in (1..end).rev() {
+        let byte = buffer[src];
+        if byte == GDL90_ESCAPEBYTE || byte == GDL90_MAGIC {
+            buffer[dest - 1] = byte ^ 0x20;
+            buffer[dest - 2] = GDL90_ESCAPEBYTE;
+            dest -= 2;
+        } else {
+            buffer[dest - 1] = byte;
+            dest -= 1;
+        }
+    }
+
+    buffer[0] = GDL90_MAGIC;
+    buffer[frame_len - 1] = GDL90_MAGIC;
+    Ok(frame_len)
+}
+
+/// Builds a complete GDL90 frame from the unescaped message id and payload in `data`.
+fn frame_data(mut data: Vec<u8>) -> Vec<u8> {
+    let crc = gdl90_crc(&data);
+    data.extend_from_slice(&crc.to_le_bytes());
+
+    let mut result = Vec::with_capacity(data.len() + 4);
+    result.push(GDL90_MAGIC);
+    result.extend(add_escapes(&data));
+    result.push(GDL90_MAGIC);
+    result
+}
+
+/// Wraps a [`Gdl90Error`] so it can be returned from binrw parsers.
+fn custom_error(pos: u64, err: Gdl90Error) -> binrw::Error {
+    binrw::Error::Custom {
+        pos,
+        err: Box::new(err),
+    }
+}
+
 /// Used to "pre-parse" a possible GDL90 message with binrw.
-/// It reads until a [`GDL90_MAGIC`] byte is found, removing the CRC so its parsed and calculated after.
-/// It returns the escaped result using [`remove_escapes`].
+/// It reads until the end [`GDL90_MAGIC`] byte is found (consuming it), so the CRC is the last 2 bytes.
+/// It returns the escaped result using [`remove_escapes`], after validating its CRC.
 #[binrw::parser(reader, endian)]
 fn parse_message_bytes() -> binrw::BinResult<Vec<u8>> {
-    // TODO: confirm that this 0x7e is not present at next message
-    let mut bytes: Vec<u8> =
-        binrw::helpers::until_exclusive(|&b| b == GDL90_MAGIC)(reader, endian, ())?;
-    // remove check seq
-    bytes.truncate(bytes.len().saturating_sub(2));
-    reader.seek(std::io::SeekFrom::Current(-3))?; // -3 because exclusive byte counts?
-    Ok(remove_escapes(bytes))
+    let pos = reader.stream_position()?;
+    let bytes: Vec<u8> = binrw::helpers::until_exclusive(|&b| b == GDL90_MAGIC)(reader, endian, ())
+        .map_err(|err| match err.is_eof() {
+            true => custom_error(pos, Gdl90Error::MissingFlag),
+            false => err,
+        })?;
+    let bytes = remove_escapes(bytes);
+    check_crc(&bytes).map_err(|err| custom_error(pos, err))?;
+    Ok(bytes)
+}
+
+/// Validates the CRC of the unescaped message id, data and CRC in `bytes`, returning it.
+pub(crate) fn check_crc(bytes: &[u8]) -> Result<u16, Gdl90Error> {
+    // message id and CRC
+    if bytes.len() < 3 {
+        return Err(Gdl90Error::Truncated);
+    }
+
+    let (data, crc) = bytes.split_at(bytes.len() - 2);
+    let expected = gdl90_crc(data);
+    let actual = u16::from_le_bytes([crc[0], crc[1]]);
+    if expected != actual {
+        return Err(Gdl90Error::BadCrc { expected, actual });
+    }
+    Ok(actual)
+}
+
+/// Used to parse the message id and data with binrw, keeping unsupported message ids
+/// as [`Gdl90DatalinkMessage::Unknown`].
+#[binrw::parser(reader, endian)]
+pub(crate) fn parse_message_data() -> binrw::BinResult<Gdl90DatalinkMessage> {
+    let pos = reader.stream_position()?;
+
+    match Gdl90DatalinkMessage::read_options(reader, endian, ()) {
+        Err(binrw::Error::EnumErrors { variant_errors, .. })
+            if variant_errors.iter().all(|(name, err)| {
+                *name == "Unknown" || matches!(err.root_cause(), binrw::Error::BadMagic { .. })
+            }) =>
+        {
+            reader.seek(SeekFrom::Start(pos))?;
+            let id = u8::read_options(reader, endian, ())?;
+            let payload = binrw::helpers::until_eof(reader, endian, ())?;
+            Ok(Gdl90DatalinkMessage::Unknown { id, payload })
+        }
+        result => result,
+    }
+}
+
+/// Used to write a GDL90 message with binrw.
+/// It writes the whole frame of the message id and data, see [`frame`].
+#[binrw::writer(writer, endian)]
+fn write_message_bytes(message_data: &Gdl90DatalinkMessage) -> binrw::BinResult<()> {
+    let mut data = Cursor::new(vec![]);
+    message_data.write_options(&mut data, endian, ())?;
+    writer.write_all(&frame_data(data.into_inner()))?;
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use types::report::{Altitude, Cord};
+    use alloc::borrow::ToOwned;
+
+    use types::ownship_geometric_altitude::Vfom;
+    use types::report::Altitude;
 
     use super::*;
-    use std::io::Cursor;
+    use binrw::io::Cursor;
 
     /* HEARTBEAT */
 
@@ -139,17 +400,131 @@ mod tests {
         let data = b"\x7E\x00\x81\x41\xDB\xD0\x08\x02\xB3\x8B\x7E";
         let parsed = Gdl90Message::read(&mut Cursor::new(data)).unwrap();
         assert_eq!(parsed.frame_check_seq, 0x8bb3);
-        match parsed.message_data {
-            Gdl90DatalinkMessage::Heartbeat { .. } => assert!(true),
-            _ => assert!(false),
-        }
-        dbg!(parsed);
+        let Gdl90DatalinkMessage::Heartbeat { heartbeat } = parsed.message_data else {
+            panic!("Expected Heartbeat message");
+        };
+        assert!(heartbeat.status_byte_2.utc_ok());
+        assert_eq!(heartbeat.seconds_since_midnight_utc(), 53467);
+        assert_eq!(heartbeat.uplink_count(), 1);
+        assert_eq!(heartbeat.basic_long_count(), 2);
     }
 
     #[test]
     fn msg_heartbeat_invalid_crc() {
         let data = b"\x7E\x00\x81\x41\xDB\xD0\x08\x02\xFF\xFF\x7E";
         assert!(Gdl90Message::read(&mut Cursor::new(data)).is_err());
+        assert!(matches!(
+            read_raw(data),
+            Err(Gdl90Error::BadCrc {
+                expected: 0x8bb3,
+                actual: 0xffff
+            })
+        ));
+    }
+
+    /* ERRORS */
+
+    #[test]
+    fn error_truncated() {
+        // heartbeat without message counts
+        let data = frame(0x00, &[0x81, 0x41, 0xDB, 0xD0]);
+        assert!(matches!(read_raw(&data), Err(Gdl90Error::Truncated)));
+
+        // only CRC
+        assert!(matches!(
+            read_raw(&[0x7E, 0x00, 0x00, 0x7E]),
+            Err(Gdl90Error::Truncated)
+        ));
+    }
+
+    #[test]
+    fn error_missing_flag() {
+        let data = b"\x00\x81\x41\xDB\xD0\x08\x02\xB3\x8B\x7E";
+        assert!(matches!(read_raw(data), Err(Gdl90Error::MissingFlag)));
+
+        let data = b"\x7E\x00\x81\x41\xDB\xD0\x08\x02\xB3\x8B";
+        assert!(matches!(read_raw(data), Err(Gdl90Error::MissingFlag)));
+    }
+
+    /* FIXED-CAPACITY BUFFERS */
+
+    #[test]
+    fn frame_into_works() {
+        let payloads: [&[u8]; 4] = [&[], &[0x81, 0x41], &[0x7E, 0x7D, 0x00, 0x7E], &[0x7D; 40]];
+        for payload in payloads {
+            let expected = frame(0x66, payload);
+            let mut buffer = [0; 100];
+            let len = frame_into(0x66, payload, &mut buffer).unwrap();
+            assert_eq!(buffer[..len], expected);
+
+            // exact size
+            let mut buffer = vec![0; expected.len()];
+            assert_eq!(
+                frame_into(0x66, payload, &mut buffer).unwrap(),
+                expected.len()
+            );
+            assert_eq!(buffer, expected);
+
+            let mut buffer = vec![0; expected.len() - 1];
+            assert!(matches!(
+                frame_into(0x66, payload, &mut buffer),
+                Err(Gdl90Error::BufferTooSmall)
+            ));
+        }
+    }
+
+    #[test]
+    fn write_into_works() {
+        let data = b"\x7E\x0A\x00\x00\x00\x00\x18\x7D\x5D\xF5\xBD\x1F\xB4\x09\x49\x88\x27\x40\x00\x82\x01\x4E\x31\x32\x33\x34\x35\x20\x20\x00\x8C\xEB\x7E";
+        let parsed = read_raw(data).unwrap();
+
+        let mut buffer = [0; decoder::GDL90_MAX_FRAME_LEN];
+        let len = write_into(&parsed, &mut buffer).unwrap();
+        assert_eq!(buffer[..len], data[..]);
+        assert_eq!(buffer[..len], write_raw(&parsed).unwrap());
+
+        for size in [0, 1, 10, data.len() - 1] {
+            let mut buffer = vec![0; size];
+            assert!(matches!(
+                write_into(&parsed, &mut buffer),
+                Err(Gdl90Error::BufferTooSmall)
+            ));
+        }
+    }
+
+    /* UNKNOWN */
+
+    #[test]
+    fn msg_unknown() {
+        let data = frame(0x66, &[0x00, 0x7E, 0x01]);
+        let parsed = read_raw(&data).unwrap();
+        assert_eq!(
+            parsed.message_data,
+            Gdl90DatalinkMessage::Unknown {
+                id: 0x66,
+                payload: vec![0x00, 0x7E, 0x01]
+            }
+        );
+
+        // written back as received
+        assert_eq!(write_raw(&parsed).unwrap(), data);
+
+        // no payload
+        let parsed = read_raw(&frame(0x66, &[])).unwrap();
+        assert_eq!(
+            parsed.message_data,
+            Gdl90DatalinkMessage::Unknown {
+                id: 0x66,
+                payload: vec![]
+            }
+        );
+    }
+
+    #[test]
+    fn msg_unknown_invalid_crc() {
+        let mut data = frame(0x66, &[0x00, 0x01]);
+        data[3] ^= 0xFF;
+        assert!(matches!(read_raw(&data), Err(Gdl90Error::BadCrc { .. })));
     }
 
     /* OWNSHIP REPORT */
@@ -262,8 +637,222 @@ mod tests {
 
     #[test]
     fn ownship_geometric_altitude() {
-        let parsed = read_raw(&[126, 11, 0, 202, 0, 12, 251, 136, 126]);
-        assert!(parsed.is_ok());
+        let data = [126, 11, 0, 202, 0, 12, 251, 136, 126];
+        let parsed = read_raw(&data).unwrap();
+        let Gdl90DatalinkMessage::OwnshipGeoometricAltitude {
+            ownship_geo_altitude,
+            vertical_metrics,
+        } = parsed.message_data
+        else {
+            panic!("Expected OwnshipGeoometricAltitude message");
+        };
+        assert_eq!(ownship_geo_altitude, 1010);
+        assert!(!vertical_metrics.vertical_warning_indicator());
+        assert_eq!(
+            vertical_metrics.vertical_figure_of_merit(),
+            Vfom::Available(12)
+        );
+
+        // encoding, altitude being rounded to 5 ft.
+        let message = Gdl90Message::new(Gdl90DatalinkMessage::OwnshipGeoometricAltitude {
+            ownship_geo_altitude: 1011,
+            vertical_metrics,
+        });
+        assert_eq!(write_raw(&message).unwrap(), data);
+    }
+
+    /* HEIGHT ABOVE TERRAIN */
+
+    #[test]
+    fn height_above_terrain() {
+        use types::height_above_terrain::HeightAboveTerrain;
+
+        let message = Gdl90Message::new(Gdl90DatalinkMessage::HeightAboveTerrain {
+            hat: HeightAboveTerrain::from_feet(-100),
+        });
+        let encoded = write_raw(&message).unwrap();
+        assert_eq!(encoded[1..4], [0x09, 0xFF, 0x9C]);
+        assert_eq!(read_raw(&encoded).unwrap(), message);
+    }
+
+    /* ENCODING */
+
+    /// Asserts that `data` is decoded and encoded back to the same frame.
+    fn assert_round_trip(data: &[u8]) {
+        let parsed = read_raw(data).unwrap();
+        let encoded = write_raw(&parsed).unwrap();
+        assert_eq!(encoded, data);
+        assert_eq!(read_raw(&encoded).unwrap(), parsed);
+    }
+
+    #[test]
+    fn round_trip_frames() {
+        assert_round_trip(b"\x7E\x00\x81\x41\xDB\xD0\x08\x02\xB3\x8B\x7E");
+        assert_round_trip(b"\x7E\x0A\x00\x00\x00\x00\x15\xA7\xE5\xBA\x47\x99\x08\xC9\x88\xFF\xE0\x00\x80\x01\x4E\x31\x32\x33\x34\x35\x20\x20\x00\x7B\xE5\x7E");
+        assert_round_trip(b"\x7E\x0A\x00\x00\x00\x00\x18\x7D\x5D\xF5\xBD\x1F\xB4\x09\x49\x88\x27\x40\x00\x82\x01\x4E\x31\x32\x33\x34\x35\x20\x20\x00\x8C\xEB\x7E");
+        assert_round_trip(b"\x7E\x0A\x01\xF0\x00\x00\x1C\x25\xE6\xB5\x0F\xF2\x16\x09\x8A\x00\x08\x00\x42\x01\x53\x74\x72\x61\x74\x75\x78\x00\x00\xDB\xF6\x7E");
+        assert_round_trip(&[126, 11, 0, 202, 0, 12, 251, 136, 126]);
+    }
+
+    #[test]
+    fn round_trip_escaped_crc() {
+        // CRC is 0x917D, so its LSB must be escaped
+        let data = b"\x7E\x09\x00\x54\x7D\x5D\x91\x7E";
+        let parsed = read_raw(data).unwrap();
+        assert_eq!(parsed.frame_check_seq, 0x917D);
+        assert_round_trip(data);
+    }
+
+    #[test]
+    fn escapes() {
+        let data = [0x00, 0x7E, 0x01, 0x7D, 0x02];
+        let escaped = add_escapes(&data);
+        assert_eq!(escaped, [0x00, 0x7D, 0x5E, 0x01, 0x7D, 0x5D, 0x02]);
+        assert_eq!(remove_escapes(escaped), data);
+    }
+
+    #[test]
+    fn frame_heartbeat() {
+        let frame = frame(0x00, &[0x81, 0x41, 0xDB, 0xD0, 0x08, 0x02]);
+        assert_eq!(frame, b"\x7E\x00\x81\x41\xDB\xD0\x08\x02\xB3\x8B\x7E");
+    }
+
+    #[test]
+    fn frame_escapes_payload_and_crc() {
+        // ownship report with an escaped latitude byte
+        let frame_bytes = frame(
+            0x0A,
+            &[
+                0x00, 0x00, 0x00, 0x00, 0x18, 0x7D, 0xF5, 0xBD, 0x1F, 0xB4, 0x09, 0x49, 0x88, 0x27,
+                0x40, 0x00, 0x82, 0x01, 0x4E, 0x31, 0x32, 0x33, 0x34, 0x35, 0x20, 0x20, 0x00,
+            ],
+        );
+        assert_eq!(frame_bytes, b"\x7E\x0A\x00\x00\x00\x00\x18\x7D\x5D\xF5\xBD\x1F\xB4\x09\x49\x88\x27\x40\x00\x82\x01\x4E\x31\x32\x33\x34\x35\x20\x20\x00\x8C\xEB\x7E");
+
+        // height above terrain with a CRC of 0x917D
+        assert_eq!(
+            frame(0x09, &[0x00, 0x54]),
+            b"\x7E\x09\x00\x54\x7D\x5D\x91\x7E"
+        );
+    }
+
+    /// Asserts that `message_data` is encoded and decoded back to the same message.
+    fn assert_encode_decode(message_data: Gdl90DatalinkMessage) {
+        let message = Gdl90Message::new(message_data);
+        let encoded = write_raw(&message).unwrap();
+        assert_eq!(encoded.first(), Some(&GDL90_MAGIC));
+        assert_eq!(encoded.last(), Some(&GDL90_MAGIC));
+        assert_eq!(read_raw(&encoded).unwrap(), message);
+    }
+
+    #[test]
+    fn encode_decode_variants() {
+        use types::foreflight::{ForeFlightAhrs, ForeFlightMessage};
+        use types::heartbeat::{Heartbeat, HeartbeatStatusByte1, HeartbeatStatusByte2};
+        use types::height_above_terrain::HeightAboveTerrain;
+        use types::icao::IcaoAddress;
+        use types::initialization::{ConfigurationByte1, ConfigurationByte2};
+        use types::ownship_geometric_altitude::{VerticalMetrics, Vfom};
+        use types::report::{
+            AddressType, CallSignType, EmergencyPriorityCodeCategory, EmmiterCategory, Participant,
+            Report, TrackHeading, TrackType, TrafficAlert, Velocity, VelocityType,
+        };
+        use types::uat_adsb::{StateVector, UatAdsbPayload, UatHeader};
+        use types::uplink_data::{UplinkHeader, UplinkPayload};
+
+        assert_encode_decode(Gdl90DatalinkMessage::Heartbeat {
+            heartbeat: Heartbeat {
+                status_byte_1: HeartbeatStatusByte1::new()
+                    .with_uat_initialized(true)
+                    .with_gps_pos_valid(true),
+                status_byte_2: HeartbeatStatusByte2::new().with_utc_ok(true),
+                timestamp: 0x7E7D,
+                message_counts: 0x0102,
+            },
+        });
+        assert_encode_decode(Gdl90DatalinkMessage::Initialization {
+            configuration_byte_1: ConfigurationByte1::new().with_cdti_ok(true),
+            configuration_byte_2: ConfigurationByte2::new().with_csa_disable(true),
+        });
+        assert_encode_decode(Gdl90DatalinkMessage::UplinkData {
+            time_of_reception: 0x7E7D7E,
+            payload: UplinkPayload {
+                header: UplinkHeader([0x7E; 8]),
+                application_data: [0x7D; 424],
+            },
+        });
+        assert_encode_decode(Gdl90DatalinkMessage::HeightAboveTerrain {
+            hat: HeightAboveTerrain::Valid(1000),
+        });
+        assert_encode_decode(Gdl90DatalinkMessage::HeightAboveTerrain {
+            hat: HeightAboveTerrain::Invalid,
+        });
+        assert_encode_decode(Gdl90DatalinkMessage::OwnshipGeoometricAltitude {
+            ownship_geo_altitude: -1250,
+            vertical_metrics: VerticalMetrics::new()
+                .with_vertical_figure_of_merit(Vfom::Available(12))
+                .with_vertical_warning_indicator(true),
+        });
+        assert_encode_decode(Gdl90DatalinkMessage::BasicReport {
+            time_of_reception: 0x123456,
+            payload: UatAdsbPayload {
+                header: UatHeader([0x00, 0xAB, 0x45, 0x49]),
+                state_vector: StateVector([0x7E; 13]),
+                elements: vec![0x7D],
+            },
+        });
+        assert_encode_decode(Gdl90DatalinkMessage::LongReport {
+            time_of_reception: 0x7E7D7E,
+            payload: UatAdsbPayload {
+                header: UatHeader([0x08, 0xAB, 0x45, 0x49]),
+                state_vector: StateVector([0x01; 13]),
+                elements: vec![0x7E; 17],
+            },
+        });
+        assert_encode_decode(Gdl90DatalinkMessage::ForeFlight {
+            message: ForeFlightMessage::Ahrs(ForeFlightAhrs {
+                roll: Some(12.5),
+                pitch: None,
+                heading: None,
+                indicated_airspeed: Some(0x7E7D),
+                true_airspeed: Some(95),
+            }),
+        });
+
+        let report = Report::new()
+            .with_traffic_alert_status(TrafficAlert::TrafficAlert)
+            .with_participant(Participant {
+                address_type: AddressType::ADSBWithICAOAddress,
+                participant_address: IcaoAddress::new(0xAB4549).unwrap(),
+            })
+            .with_latitude(44.90708)
+            .with_longitude(-122.99488)
+            .with_altitude(Altitude::Valid(5000))
+            .with_velocity(Velocity {
+                h_vel: VelocityType::Horizontal(123),
+                v_vel: VelocityType::Vertical(64),
+            })
+            .with_track_heading(TrackHeading::new(TrackType::TrueTrackAngle, 45.0))
+            .with_emmiter_cattegory(EmmiterCategory::Light)
+            .with_call_sign(CallSignType {
+                tail_number: "N825V".to_owned(),
+            })
+            .with_emergency_priority_code(EmergencyPriorityCodeCategory::NoEmergency);
+        let encoded = write_raw(&Gdl90Message::new(Gdl90DatalinkMessage::TrafficReport {
+            report,
+        }))
+        .unwrap();
+        let parsed = read_raw(&encoded).unwrap();
+        if let Gdl90DatalinkMessage::TrafficReport { report } = parsed.message_data {
+            assert_eq!(report.participant().participant_address.value(), 0xAB4549);
+            assert!((report.latitude() - 44.90708).abs() < 0.0001);
+            assert!((report.longitude() - -122.99488).abs() < 0.0001);
+            assert_eq!(report.altitude(), Altitude::Valid(5000));
+            assert_eq!(report.track_heading().angle(), Some(45.0));
+            assert_eq!(report.call_sign().tail_number, "N825V");
+        } else {
+            panic!("Expected TrafficReport message");
+        }
     }
 
     #[test]
@@ -273,9 +862,9 @@ mod tests {
         ])
         .unwrap();
         assert_eq!(parsed.frame_check_seq, 0x8bb3);
-        match parsed.message_data {
-            Gdl90DatalinkMessage::Heartbeat { .. } => assert!(true),
-            _ => assert!(false),
-        }
+        assert!(matches!(
+            parsed.message_data,
+            Gdl90DatalinkMessage::Heartbeat { .. }
+        ));
     }
 }
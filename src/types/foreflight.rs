@@ -0,0 +1,365 @@
+//! ForeFlight GDL90 extension messages (message ID 0x65).
+//!
+//! Portable receivers use these to identify themselves and to report their AHRS data.
+//! All multi-byte fields are transmitted most significant byte first.
+//!
+//! | Sub-ID | Name           | Type                |
+//! |--------|----------------|---------------------|
+//! | 0      | ID message     | [`ForeFlightId`]    |
+//! | 1      | AHRS message   | [`ForeFlightAhrs`]  |
+//!
+//! ## Example
+//! ```
+//! use gdl90::datalink::Gdl90DatalinkMessage;
+//! use gdl90::types::foreflight::{AltitudeDatum, ForeFlightId, ForeFlightMessage};
+//!
+//! let id = ForeFlightId::new(Some(1234), "Stratux", "Stratux receiver")
+//!     .with_altitude_datum(AltitudeDatum::Msl);
+//! let message = gdl90::Gdl90Message::new(Gdl90DatalinkMessage::ForeFlight {
+//!     message: ForeFlightMessage::Id(id),
+//! });
+//! let bytes = gdl90::write_raw(&message).unwrap();
+//! assert_eq!(gdl90::read_raw(&bytes).unwrap(), message);
+//! ```
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use binrw::binrw;
+
+use super::round;
+
+/// Serial number meaning that it is not available.
+const SERIAL_INVALID: u64 = u64::MAX;
+
+/// Length of the device name.
+const NAME_LEN: usize = 8;
+
+/// Length of the device long name.
+const LONG_NAME_LEN: usize = 16;
+
+/// Roll and pitch value meaning that they are not available.
+const ATTITUDE_INVALID: i16 = 0x7FFF;
+
+/// Heading and airspeeds value meaning that they are not available.
+const AHRS_U16_INVALID: u16 = 0xFFFF;
+
+/// ForeFlight extension message, depending on its sub-ID.
+#[binrw]
+#[brw(big)]
+#[derive(Debug, PartialEq, Clone)]
+pub enum ForeFlightMessage {
+    /// Device identification.
+    #[brw(magic = b"\x00")]
+    Id(ForeFlightId),
+
+    /// Attitude and air data.
+    #[brw(magic = b"\x01")]
+    Ahrs(ForeFlightAhrs),
+
+    /// Any sub-message which is not supported, kept as is.
+    Unknown {
+        sub_id: u8,
+        #[br(parse_with = binrw::helpers::until_eof)]
+        data: Vec<u8>,
+    },
+}
+
+/// ForeFlight ID message (sub-ID 0), only version 1 being supported.
+///
+/// | Byte # | Name             | Size  | Value                                         |
+/// |--------|------------------|-------|-----------------------------------------------|
+/// | 1      | Version          | 1     | 1                                             |
+/// | 2-9    | Serial Number    | 8     | 0xFFFFFFFFFFFFFFFF if invalid                 |
+/// | 10-17  | Device Name      | 8     | UTF-8                                         |
+/// | 18-33  | Device Long Name | 16    | UTF-8                                         |
+/// | 34-37  | Capabilities     | 4     | Bit 0: geometric altitude datum (0 = WGS-84, 1 = MSL) |
+#[binrw]
+#[brw(big, magic = b"\x01")]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ForeFlightId {
+    /// Device serial number.
+    #[br(map = |x: u64| (x != SERIAL_INVALID).then_some(x))]
+    #[bw(map = |x: &Option<u64>| x.unwrap_or(SERIAL_INVALID))]
+    pub serial_number: Option<u64>,
+
+    /// Device name, up to 8 bytes when encoded.
+    #[br(map = |x: [u8; NAME_LEN]| decode_name(&x))]
+    #[bw(map = |x: &String| encode_name::<NAME_LEN>(x))]
+    pub name: String,
+
+    /// Device long name, up to 16 bytes when encoded.
+    #[br(map = |x: [u8; LONG_NAME_LEN]| decode_name(&x))]
+    #[bw(map = |x: &String| encode_name::<LONG_NAME_LEN>(x))]
+    pub long_name: String,
+
+    /// Capabilities mask, see [`ForeFlightId::altitude_datum`].
+    pub capabilities: u32,
+}
+
+/// Datum of the geometric altitudes reported by the device
+/// (eg. [`Gdl90DatalinkMessage::OwnshipGeoometricAltitude`](crate::datalink::Gdl90DatalinkMessage::OwnshipGeoometricAltitude)).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AltitudeDatum {
+    /// WGS-84 ellipsoid, as defined by the GDL90 specification.
+    Wgs84Ellipsoid,
+    /// Mean Sea Level.
+    Msl,
+}
+
+impl ForeFlightId {
+    /// Creates an ID message with no capabilities.
+    pub fn new(serial_number: Option<u64>, name: &str, long_name: &str) -> Self {
+        Self {
+            serial_number,
+            name: name.to_owned(),
+            long_name: long_name.to_owned(),
+            capabilities: 0,
+        }
+    }
+
+    /// Geometric altitude datum, capability bit 0.
+    pub fn altitude_datum(&self) -> AltitudeDatum {
+        match self.capabilities & 1 {
+            0 => AltitudeDatum::Wgs84Ellipsoid,
+            _ => AltitudeDatum::Msl,
+        }
+    }
+
+    /// Sets the geometric altitude datum.
+    pub fn set_altitude_datum(&mut self, datum: AltitudeDatum) {
+        self.capabilities = (self.capabilities & !1) | (datum == AltitudeDatum::Msl) as u32;
+    }
+
+    /// Returns a copy of the message with the geometric altitude datum set.
+    pub fn with_altitude_datum(mut self, datum: AltitudeDatum) -> Self {
+        self.set_altitude_datum(datum);
+        self
+    }
+}
+
+/// Decodes a name, ignoring its padding.
+fn decode_name(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .trim_end_matches(['\0', ' '])
+        .to_owned()
+}
+
+/// Encodes a name, truncated (on a character boundary) or padded with zeros to `N` bytes.
+fn encode_name<const N: usize>(name: &str) -> [u8; N] {
+    let mut len = name.len().min(N);
+    while !name.is_char_boundary(len) {
+        len -= 1;
+    }
+    let mut output = [0; N];
+    output[..len].copy_from_slice(&name.as_bytes()[..len]);
+    output
+}
+
+/// ForeFlight AHRS message (sub-ID 1).
+///
+/// | Byte # | Name             | Size  | Value                                                 |
+/// |--------|------------------|-------|-------------------------------------------------------|
+/// | 1-2    | Roll             | 2     | Resolution 0.1 deg, ±1800, 0x7FFF if invalid          |
+/// | 3-4    | Pitch            | 2     | Resolution 0.1 deg, ±1800, 0x7FFF if invalid          |
+/// | 5-6    | Heading          | 2     | Bit 15: magnetic, bits 14-0: 0.1 deg, ±3600, 0xFFFF if invalid |
+/// | 7-8    | Indicated Airspeed | 2   | Knots, 0xFFFF if invalid                              |
+/// | 9-10   | True Airspeed    | 2     | Knots, 0xFFFF if invalid                              |
+#[binrw]
+#[brw(big)]
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct ForeFlightAhrs {
+    /// Roll in degrees, positive being right wing down.
+    #[br(map = decode_attitude)]
+    #[bw(map = |x: &Option<f32>| encode_attitude(*x))]
+    pub roll: Option<f32>,
+
+    /// Pitch in degrees, positive being nose up.
+    #[br(map = decode_attitude)]
+    #[bw(map = |x: &Option<f32>| encode_attitude(*x))]
+    pub pitch: Option<f32>,
+
+    /// Heading.
+    #[br(map = AhrsHeading::from_raw)]
+    #[bw(map = |x: &Option<AhrsHeading>| AhrsHeading::to_raw(*x))]
+    pub heading: Option<AhrsHeading>,
+
+    /// Indicated airspeed in knots.
+    #[br(map = |x: u16| (x != AHRS_U16_INVALID).then_some(x))]
+    #[bw(map = |x: &Option<u16>| x.map_or(AHRS_U16_INVALID, |x| x.min(AHRS_U16_INVALID - 1)))]
+    pub indicated_airspeed: Option<u16>,
+
+    /// True airspeed in knots.
+    #[br(map = |x: u16| (x != AHRS_U16_INVALID).then_some(x))]
+    #[bw(map = |x: &Option<u16>| x.map_or(AHRS_U16_INVALID, |x| x.min(AHRS_U16_INVALID - 1)))]
+    pub true_airspeed: Option<u16>,
+}
+
+/// Kind of an [`AhrsHeading`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AhrsHeadingType {
+    True,
+    Magnetic,
+}
+
+/// AHRS heading, see [`ForeFlightAhrs::heading`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct AhrsHeading {
+    pub kind: AhrsHeadingType,
+
+    /// Heading in degrees, from -360 to 360.
+    pub degrees: f32,
+}
+
+impl AhrsHeading {
+    /// Decodes the heading from its 16 bits, the 15-bit angle being signed.
+    fn from_raw(raw: u16) -> Option<Self> {
+        if raw == AHRS_U16_INVALID {
+            return None;
+        }
+        let kind = match raw & 0x8000 {
+            0 => AhrsHeadingType::True,
+            _ => AhrsHeadingType::Magnetic,
+        };
+        // sign extension of the 15-bit angle
+        let tenths = ((raw << 1) as i16) >> 1;
+        Some(Self {
+            kind,
+            degrees: tenths as f32 / 10.0,
+        })
+    }
+
+    /// Encodes the heading to its 16 bits, the angle being saturated to ±360 degrees.
+    fn to_raw(heading: Option<Self>) -> u16 {
+        let Some(heading) = heading else {
+            return AHRS_U16_INVALID;
+        };
+        let tenths = round(heading.degrees * 10.0).clamp(-3600.0, 3600.0) as i16;
+        let kind = match heading.kind {
+            AhrsHeadingType::True => 0,
+            AhrsHeadingType::Magnetic => 0x8000,
+        };
+        match kind | (tenths as u16 & 0x7FFF) {
+            // magnetic -0.1 degrees would be the invalid value
+            AHRS_U16_INVALID => 0x8000,
+            raw => raw,
+        }
+    }
+}
+
+/// Decodes a roll or pitch, in 0.1 degrees.
+fn decode_attitude(raw: i16) -> Option<f32> {
+    (raw != ATTITUDE_INVALID).then(|| raw as f32 / 10.0)
+}
+
+/// Encodes a roll or pitch, in 0.1 degrees saturated to ±180 degrees.
+fn encode_attitude(degrees: Option<f32>) -> i16 {
+    degrees.map_or(ATTITUDE_INVALID, |x| {
+        round(x * 10.0).clamp(-1800.0, 1800.0) as i16
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use binrw::io::Cursor;
+    use binrw::{BinRead, BinWrite};
+
+    fn write(message: &ForeFlightMessage) -> Vec<u8> {
+        let mut output = Cursor::new(vec![]);
+        message.write(&mut output).unwrap();
+        output.into_inner()
+    }
+
+    #[test]
+    fn id_message_works() {
+        let mut data = vec![0x00, 0x01];
+        data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0x04, 0xD2]);
+        data.extend_from_slice(b"Stratux\0");
+        data.extend_from_slice(b"Stratux receiver");
+        data.extend_from_slice(&[0, 0, 0, 1]);
+        assert_eq!(data.len(), 38);
+
+        let message = ForeFlightMessage::read(&mut Cursor::new(&data)).unwrap();
+        let ForeFlightMessage::Id(id) = &message else {
+            panic!("Expected ID message");
+        };
+        assert_eq!(id.serial_number, Some(1234));
+        assert_eq!(id.name, "Stratux");
+        assert_eq!(id.long_name, "Stratux receiver");
+        assert_eq!(id.altitude_datum(), AltitudeDatum::Msl);
+        assert_eq!(write(&message), data);
+
+        // invalid serial number, names being truncated
+        let id = ForeFlightId::new(None, "Receiver ü", "").with_altitude_datum(AltitudeDatum::Msl);
+        let id = id.with_altitude_datum(AltitudeDatum::Wgs84Ellipsoid);
+        let mut data = write(&ForeFlightMessage::Id(id));
+        assert_eq!(data[2..10], [0xFF; 8]);
+        assert_eq!(&data[10..18], b"Receiver");
+        assert_eq!(data[34..], [0, 0, 0, 0]);
+
+        // unsupported version
+        data[1] = 2;
+        let message = ForeFlightMessage::read(&mut Cursor::new(&data)).unwrap();
+        assert!(matches!(
+            message,
+            ForeFlightMessage::Unknown { sub_id: 0, .. }
+        ));
+    }
+
+    #[test]
+    fn ahrs_message_works() {
+        let data = [
+            0x01, 0xFF, 0x9C, 0x00, 0x32, 0x8E, 0x10, 0x00, 0x78, 0xFF, 0xFF,
+        ];
+        let message = ForeFlightMessage::read(&mut Cursor::new(&data)).unwrap();
+        let ForeFlightMessage::Ahrs(ahrs) = message else {
+            panic!("Expected AHRS message");
+        };
+        assert_eq!(ahrs.roll, Some(-10.0));
+        assert_eq!(ahrs.pitch, Some(5.0));
+        assert_eq!(
+            ahrs.heading,
+            Some(AhrsHeading {
+                kind: AhrsHeadingType::Magnetic,
+                degrees: 360.0
+            })
+        );
+        assert_eq!(ahrs.indicated_airspeed, Some(120));
+        assert_eq!(ahrs.true_airspeed, None);
+        assert_eq!(write(&message), data);
+
+        // negative heading
+        let heading = AhrsHeading::from_raw(0x7FF6).unwrap();
+        assert_eq!(heading.kind, AhrsHeadingType::True);
+        assert_eq!(heading.degrees, -1.0);
+        assert_eq!(AhrsHeading::to_raw(Some(heading)), 0x7FF6);
+        let heading = AhrsHeading {
+            kind: AhrsHeadingType::Magnetic,
+            degrees: -0.1,
+        };
+        assert_eq!(AhrsHeading::to_raw(Some(heading)), 0x8000);
+
+        // invalid sentinels
+        let data = write(&ForeFlightMessage::Ahrs(ForeFlightAhrs::default()));
+        assert_eq!(
+            data,
+            [0x01, 0x7F, 0xFF, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]
+        );
+    }
+
+    #[test]
+    fn unknown_sub_id_works() {
+        let data = [0x7F, 0x01, 0x02];
+        let message = ForeFlightMessage::read(&mut Cursor::new(&data)).unwrap();
+        assert_eq!(
+            message,
+            ForeFlightMessage::Unknown {
+                sub_id: 0x7F,
+                data: vec![0x01, 0x02]
+            }
+        );
+        assert_eq!(write(&message), data);
+    }
+}
@@ -1,74 +1,256 @@
 //! GDL90 Heartbeat custom types. 560-1058-00 Rev A - ref 3.1.x
+//!
+//! ## Example
+//! ```
+//! use gdl90::datalink::Gdl90DatalinkMessage;
+//!
+//! let parsed = gdl90::read_raw(&[0x7E, 0x00, 0x81, 0x41, 0xDB, 0xD0, 0x08, 0x02, 0xB3, 0x8B, 0x7E]).unwrap();
+//! if let Gdl90DatalinkMessage::Heartbeat { heartbeat } = parsed.message_data {
+//!     assert_eq!(heartbeat.seconds_since_midnight_utc(), 53467);
+//!     assert_eq!(heartbeat.uplink_count(), 1);
+//!     assert_eq!(heartbeat.basic_long_count(), 2);
+//! }
+//! ```
 
-use binrw::BinRead;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use binrw::{binrw, BinRead, BinWrite};
 use modular_bitfield::{bitfield, prelude::B4};
 
-/// Heartbeat Status Byte 1. 560-1058-00 Rev A - ref 3.1.1
+/// Seconds in a day.
+const SECONDS_PER_DAY: u64 = 86400;
+
+/// Maximum value of the 5-bit uplink message count.
+const MAX_UPLINK_COUNT: u8 = 0x1F;
+
+/// Maximum value of the 10-bit basic and long message count.
+const MAX_BASIC_LONG_COUNT: u16 = 0x3FF;
+
+/// Heartbeat message content. 560-1058-00 Rev A - ref 3.1
 ///
-/// | Bit | Description        | Value | Meaning                                     |
-/// |-----|--------------------|-------|---------------------------------------------|
-/// | 7   | GPS Pos Valid      | 1     | Position is available for ADS-B Tx          |
-/// | 6   | Maint Req'd        | 1     | GDL 90 Maintenance Req'd                    |
-/// | 5   | IDENT              | 1     | IDENT talkback                              |
-/// | 4   | Addr Type          | 1     | Address Type talkback                       |
-/// | 3   | GPS Batt Low       | 1     | GPS Battery low voltage                     |
-/// | 2   | RATCS              | 1     | ATC Services talkback                       |
-/// | 1   | Reserved           | -     | -                                           |
-/// | 0   | UAT Initialized    | 1     | GDL 90 is initialized                       |
-#[bitfield]
-#[derive(BinRead, Debug)]
-#[br(little)]
-pub struct HeartbeatStatusByte1 {
-    /// This bit is set to `true` in all Heartbeat messages.
-    pub uat_initialized: bool,
-
-    /// Set to `false` in equipment that complies with this version of the specification.
-    #[skip]
-    reserved: bool,
-
-    /// Set to the present state of the Receiving ATC Services indication in the transmitted ADS-B messages.
-    pub ratcs: bool,
-
-    /// Whether the GDL 90 needs maintenance to replace its internal GPS battery.
-    pub gps_batt_low: bool,
-
-    /// Whether the GDL 90 is transmitting ADS-B messages using a temporary self-assigned (“anonymous”) address.
-    pub addr_type: bool,
-
-    /// Whether the GDL 90 has set the `IDENT` indication in its transmitted ADS-B messages.
-    pub ident: bool,
-
-    /// Whether the GDL 90 has detected a problem and requires maintainence.
-    pub maint_reqd: bool,
-
-    /// Whether the GDL 90 has a valid position fix for ADS-B messages.
-    pub gps_pos_valid: bool,
+/// | Byte # | Name             | Size  | Value                                             |
+/// |--------|------------------|-------|---------------------------------------------------|
+/// | 1      |Status Byte 1     | 1     | see [`HeartbeatStatusByte1`]                      |
+/// | 2      |Status Byte 2     | 1     | see [`HeartbeatStatusByte2`]                      |
+/// | 3-4    |Timestamp         | 2     | Seconds since 0000Z, bits 15-0 (LSB byte first)   |
+/// | 5-6    |Message Counts    | 2     | see [`Heartbeat::uplink_count`] and [`Heartbeat::basic_long_count`] |
+#[binrw]
+#[brw(little)]
+#[derive(Debug, PartialEq)]
+pub struct Heartbeat {
+    pub status_byte_1: HeartbeatStatusByte1,
+
+    pub status_byte_2: HeartbeatStatusByte2,
+
+    /// Seconds since 0000Z, bits 15-0. Bit 16 is [`HeartbeatStatusByte2::timestamp_msb`],
+    /// see [`Heartbeat::seconds_since_midnight_utc`].
+    pub timestamp: u16,
+
+    /// Messages received in the previous second. Unlike the timestamp, the most significant byte comes first:
+    ///
+    /// | Bits  | Description                        |
+    /// |-------|------------------------------------|
+    /// | 15-11 | Uplink messages (5 bits)           |
+    /// | 10    | Reserved                           |
+    /// | 9-0   | Basic and Long messages (10 bits)  |
+    #[brw(big)]
+    pub message_counts: u16,
 }
 
-/// Heartbeat Status Byte 2. 560-1058-00 Rev A - ref 3.1.2
-///
-/// | Bit | Description        | Value | Meaning                                     |
-/// |-----|--------------------|-------|---------------------------------------------|
-/// | 7   | Time Stamp (MS bit)| 1     | Seconds since 0000Z, bit 16                 |
-/// | 6   | CSA Requested      | 1     | CSA has been requested                      |
-/// | 5   | CSA Not Available  | 1     | CSA is not available at this time           |
-/// | 4   | Reserved           | -     | -                                           |
-/// | 3   | Reserved           | -     | -                                           |
-/// | 2   | Reserved           | -     | -                                           |
-/// | 1   | Reserved           | -     | -                                           |
-/// | 0   | UTC OK             | 1     | UTC timing is valid                         |
-#[bitfield]
-#[derive(BinRead, Debug)]
-#[br(little)]
-pub struct HeartbeatStatusByte2 {
-    pub utc_ok: bool,
-
-    #[skip]
-    reserved: B4,
-
-    pub csa_not_available: bool,
-
-    pub csa_requested: bool,
-
-    pub timestamp_msb: bool,
+impl Heartbeat {
+    /// Seconds since 0000Z (17 bits), merging [`Heartbeat::timestamp`] with [`HeartbeatStatusByte2::timestamp_msb`].
+    ///
+    /// Only meaningful if [`HeartbeatStatusByte2::utc_ok`] is set.
+    pub fn seconds_since_midnight_utc(&self) -> u32 {
+        ((self.status_byte_2.timestamp_msb() as u32) << 16) | self.timestamp as u32
+    }
+
+    /// Sets the seconds since 0000Z, only the 17 least significant bits being kept.
+    pub fn set_seconds_since_midnight_utc(&mut self, seconds: u32) {
+        self.timestamp = seconds as u16;
+        self.status_byte_2.set_timestamp_msb(seconds & 0x10000 != 0);
+    }
+
+    /// Returns the time of the heartbeat, on the UTC day of `date`.
+    ///
+    /// Only meaningful if [`HeartbeatStatusByte2::utc_ok`] is set.
+    #[cfg(feature = "std")]
+    pub fn to_utc_time(&self, date: SystemTime) -> SystemTime {
+        let since_epoch = date.duration_since(UNIX_EPOCH).unwrap_or_default();
+        UNIX_EPOCH + self.to_utc_since_epoch(since_epoch)
+    }
+
+    /// Returns the time of the heartbeat on the UTC day of `date`, both being durations since the
+    /// Unix epoch.
+    ///
+    /// Only meaningful if [`HeartbeatStatusByte2::utc_ok`] is set.
+    pub fn to_utc_since_epoch(&self, date: Duration) -> Duration {
+        let midnight = date.as_secs() - date.as_secs() % SECONDS_PER_DAY;
+        Duration::from_secs(midnight + self.seconds_since_midnight_utc() as u64)
+    }
+
+    /// Number of UAT uplink messages received in the previous second.
+    pub fn uplink_count(&self) -> u8 {
+        (self.message_counts >> 11) as u8
+    }
+
+    /// Number of Basic and Long UAT messages received in the previous second.
+    pub fn basic_long_count(&self) -> u16 {
+        self.message_counts & MAX_BASIC_LONG_COUNT
+    }
+
+    /// Sets the message counts, saturating them to their 5 and 10 bits.
+    pub fn set_message_counts(&mut self, uplink: u8, basic_long: u16) {
+        let uplink = uplink.min(MAX_UPLINK_COUNT) as u16;
+        self.message_counts = (uplink << 11) | basic_long.min(MAX_BASIC_LONG_COUNT);
+    }
+}
+
+pub use self::bitfields::{HeartbeatStatusByte1, HeartbeatStatusByte2};
+
+// Lints triggered by the `modular_bitfield` expansion.
+#[allow(unused_parens, clippy::identity_op, clippy::new_without_default)]
+mod bitfields {
+    use super::*;
+
+    /// Heartbeat Status Byte 1. 560-1058-00 Rev A - ref 3.1.1
+    ///
+    /// | Bit | Description        | Value | Meaning                                     |
+    /// |-----|--------------------|-------|---------------------------------------------|
+    /// | 7   | GPS Pos Valid      | 1     | Position is available for ADS-B Tx          |
+    /// | 6   | Maint Req'd        | 1     | GDL 90 Maintenance Req'd                    |
+    /// | 5   | IDENT              | 1     | IDENT talkback                              |
+    /// | 4   | Addr Type          | 1     | Address Type talkback                       |
+    /// | 3   | GPS Batt Low       | 1     | GPS Battery low voltage                     |
+    /// | 2   | RATCS              | 1     | ATC Services talkback                       |
+    /// | 1   | Reserved           | -     | -                                           |
+    /// | 0   | UAT Initialized    | 1     | GDL 90 is initialized                       |
+    #[bitfield]
+    #[derive(BinRead, BinWrite, Debug, PartialEq)]
+    #[brw(little)]
+    pub struct HeartbeatStatusByte1 {
+        /// This bit is set to `true` in all Heartbeat messages.
+        pub uat_initialized: bool,
+
+        /// Set to `false` in equipment that complies with this version of the specification.
+        #[skip]
+        reserved: bool,
+
+        /// Set to the present state of the Receiving ATC Services indication in the transmitted ADS-B messages.
+        pub ratcs: bool,
+
+        /// Whether the GDL 90 needs maintenance to replace its internal GPS battery.
+        pub gps_batt_low: bool,
+
+        /// Whether the GDL 90 is transmitting ADS-B messages using a temporary self-assigned (“anonymous”) address.
+        pub addr_type: bool,
+
+        /// Whether the GDL 90 has set the `IDENT` indication in its transmitted ADS-B messages.
+        pub ident: bool,
+
+        /// Whether the GDL 90 has detected a problem and requires maintainence.
+        pub maint_reqd: bool,
+
+        /// Whether the GDL 90 has a valid position fix for ADS-B messages.
+        pub gps_pos_valid: bool,
+    }
+
+    /// Heartbeat Status Byte 2. 560-1058-00 Rev A - ref 3.1.2
+    ///
+    /// | Bit | Description        | Value | Meaning                                     |
+    /// |-----|--------------------|-------|---------------------------------------------|
+    /// | 7   | Time Stamp (MS bit)| 1     | Seconds since 0000Z, bit 16                 |
+    /// | 6   | CSA Requested      | 1     | CSA has been requested                      |
+    /// | 5   | CSA Not Available  | 1     | CSA is not available at this time           |
+    /// | 4   | Reserved           | -     | -                                           |
+    /// | 3   | Reserved           | -     | -                                           |
+    /// | 2   | Reserved           | -     | -                                           |
+    /// | 1   | Reserved           | -     | -                                           |
+    /// | 0   | UTC OK             | 1     | UTC timing is valid                         |
+    #[bitfield]
+    #[derive(BinRead, BinWrite, Debug, PartialEq)]
+    #[brw(little)]
+    pub struct HeartbeatStatusByte2 {
+        pub utc_ok: bool,
+
+        #[skip]
+        reserved: B4,
+
+        pub csa_not_available: bool,
+
+        pub csa_requested: bool,
+
+        pub timestamp_msb: bool,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heartbeat(timestamp_msb: bool, timestamp: u16, message_counts: u16) -> Heartbeat {
+        Heartbeat {
+            status_byte_1: HeartbeatStatusByte1::new().with_uat_initialized(true),
+            status_byte_2: HeartbeatStatusByte2::new()
+                .with_utc_ok(true)
+                .with_timestamp_msb(timestamp_msb),
+            timestamp,
+            message_counts,
+        }
+    }
+
+    #[test]
+    fn timestamp_works() {
+        let mut object = heartbeat(true, 0x517F, 0);
+        assert_eq!(object.seconds_since_midnight_utc(), 86399);
+
+        // 2024-03-01T12:34:56Z
+        let date = Duration::from_secs(1709296496);
+        let time = object.to_utc_since_epoch(date);
+        assert_eq!(time.as_secs(), 1709337599);
+
+        object.set_seconds_since_midnight_utc(12 * 3600 + 34 * 60 + 56);
+        assert_eq!(object.timestamp, 45296);
+        assert!(!object.status_byte_2.timestamp_msb());
+        assert_eq!(object.seconds_since_midnight_utc(), 45296);
+        assert_eq!(object.to_utc_since_epoch(date), date);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn utc_time_works() {
+        let object = heartbeat(true, 0x517F, 0);
+
+        // 2024-03-01T12:34:56Z
+        let date = UNIX_EPOCH + Duration::from_secs(1709296496);
+        let time = object.to_utc_time(date);
+        assert_eq!(
+            time.duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            1709337599
+        );
+    }
+
+    #[test]
+    fn message_counts_work() {
+        let mut object = heartbeat(false, 0, 0);
+        object.set_message_counts(31, 1023);
+        assert_eq!(object.message_counts, 0xFBFF);
+        assert_eq!(object.uplink_count(), 31);
+        assert_eq!(object.basic_long_count(), 1023);
+
+        object.set_message_counts(200, 2000);
+        assert_eq!(object.uplink_count(), 31);
+        assert_eq!(object.basic_long_count(), 1023);
+
+        // timestamp first byte is the LSB, message counts first byte is the MSB
+        let object = Heartbeat::read(&mut binrw::io::Cursor::new([
+            0x81, 0xC1, 0xDB, 0xD0, 0x08, 0x02,
+        ]))
+        .unwrap();
+        assert_eq!(object.seconds_since_midnight_utc(), 0x1D0DB);
+        assert_eq!(object.uplink_count(), 1);
+        assert_eq!(object.basic_long_count(), 2);
+    }
 }
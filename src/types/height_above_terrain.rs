@@ -0,0 +1,95 @@
+//! GDL90 Height Above Terrain custom types. 560-1058-00 Rev A - ref 3.7
+
+use binrw::binrw;
+
+/// Value meaning that the Height Above Terrain is not valid.
+const HAT_INVALID: i16 = i16::MIN;
+
+/// Height Above Terrain, signed with a 1 foot resolution and transmitted most significant byte first.
+///
+/// | Value         | Meaning                          |
+/// |---------------|----------------------------------|
+/// | 0x8000        | Height Above Terrain not valid   |
+/// | Any other     | Height Above Terrain, in feet    |
+#[binrw]
+#[brw(big)]
+#[br(map = Self::from_raw)]
+#[bw(map = |x: &Self| x.to_raw())]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum HeightAboveTerrain {
+    /// Height Above Terrain in feet, from -32767 to 32767.
+    Valid(i16),
+
+    #[default]
+    Invalid,
+}
+
+impl HeightAboveTerrain {
+    /// Creates a valid Height Above Terrain from feet, saturating values which do not fit.
+    pub fn from_feet(feet: i32) -> Self {
+        HeightAboveTerrain::Valid(feet.clamp(-(i16::MAX as i32), i16::MAX as i32) as i16)
+    }
+
+    /// Height Above Terrain in feet, `None` if it is not valid.
+    pub fn feet(self) -> Option<i16> {
+        match self {
+            HeightAboveTerrain::Valid(x) => Some(x),
+            HeightAboveTerrain::Invalid => None,
+        }
+    }
+
+    /// Decodes the Height Above Terrain from its 16 bits.
+    pub fn from_raw(raw: i16) -> Self {
+        match raw {
+            HAT_INVALID => HeightAboveTerrain::Invalid,
+            x => HeightAboveTerrain::Valid(x),
+        }
+    }
+
+    /// Encodes the Height Above Terrain to its 16 bits, `Valid(-32768)` being saturated to -32767.
+    pub fn to_raw(self) -> i16 {
+        match self {
+            HeightAboveTerrain::Valid(x) => x.max(-i16::MAX),
+            HeightAboveTerrain::Invalid => HAT_INVALID,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use binrw::io::Cursor;
+    use binrw::{BinRead, BinWrite};
+
+    #[test]
+    fn height_above_terrain_works() {
+        let read = |bytes: [u8; 2]| HeightAboveTerrain::read(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(read([0x03, 0xE8]), HeightAboveTerrain::Valid(1000));
+        assert_eq!(read([0xFF, 0x9C]), HeightAboveTerrain::Valid(-100));
+        assert_eq!(read([0x80, 0x00]), HeightAboveTerrain::Invalid);
+        assert_eq!(read([0x80, 0x01]), HeightAboveTerrain::Valid(-32767));
+
+        let write = |hat: HeightAboveTerrain| {
+            let mut output = Cursor::new(vec![]);
+            hat.write(&mut output).unwrap();
+            output.into_inner()
+        };
+        assert_eq!(write(HeightAboveTerrain::Valid(1000)), [0x03, 0xE8]);
+        assert_eq!(write(HeightAboveTerrain::Invalid), [0x80, 0x00]);
+        assert_eq!(write(HeightAboveTerrain::Valid(i16::MIN)), [0x80, 0x01]);
+    }
+
+    #[test]
+    fn feet_works() {
+        assert_eq!(HeightAboveTerrain::from_feet(250).feet(), Some(250));
+        assert_eq!(
+            HeightAboveTerrain::from_feet(100_000).feet(),
+            Some(i16::MAX)
+        );
+        assert_eq!(
+            HeightAboveTerrain::from_feet(-100_000).feet(),
+            Some(-i16::MAX)
+        );
+        assert_eq!(HeightAboveTerrain::Invalid.feet(), None);
+    }
+}
@@ -1,62 +1,208 @@
 //! GDL90 Initialization message. 560-1058-00 Rev A - ref 3.2.x
+//!
+//! The Initialization message is sent by the Display to the GDL 90 to configure it.
+//!
+//! ## Example
+//! ```
+//! use gdl90::types::initialization::InitializationBuilder;
+//!
+//! let frame = InitializationBuilder::new()
+//!     .with_cdti_ok(true)
+//!     .with_csa_audio_disable(true)
+//!     .to_frame();
+//! assert_eq!(frame[1..4], [0x02, 0x01, 0x02]);
+//! // write to GDL 90..
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::datalink::Gdl90DatalinkMessage;
+use crate::frame;
+use binrw::{BinRead, BinWrite};
 
-use binrw::BinRead;
 use modular_bitfield::{
     bitfield,
     prelude::{B4, B6},
 };
 
-/// Initialization Configuration Byte 1, using bitfields.
-///
-/// | Bit | Description                        | Value | Meaning                              |
-/// |-----|------------------------------------|-------|--------------------------------------|
-/// | 7   | Reserved                           | -     | -                                    |
-/// | 6   | Audio Test                         | 1     | Initiate audio test                  |
-/// | 5   | Reserved                           | -     | -                                    |
-/// | 4   | Reserved                           | -     | -                                    |
-/// | 3   | Reserved                           | -     | -                                    |
-/// | 2   | Reserved                           | -     | -                                    |
-/// | 1   | Audio Inhibit                      | 1     | Suppress GDL 90 audio output         |
-/// | 0   | CDTI OK                            | 1     | CDTI capability is operating         |
-#[bitfield]
-#[derive(BinRead, Debug)]
-#[br(little)]
-pub struct ConfigurationByte1 {
-    pub cdti_ok: bool,
-    pub audio_inhibit: bool,
-    #[skip]
-    reserved_2345: B4,
-    pub audio_test: bool,
-    #[skip]
-    reserved_7: bool,
+pub use self::bitfields::{ConfigurationByte1, ConfigurationByte2};
+
+// Lints triggered by the `modular_bitfield` expansion.
+#[allow(unused_parens, clippy::identity_op, clippy::new_without_default)]
+mod bitfields {
+    use super::*;
+
+    /// Initialization Configuration Byte 1, using bitfields.
+    ///
+    /// | Bit | Description                        | Value | Meaning                              |
+    /// |-----|------------------------------------|-------|--------------------------------------|
+    /// | 7   | Reserved                           | -     | -                                    |
+    /// | 6   | Audio Test                         | 1     | Initiate audio test                  |
+    /// | 5   | Reserved                           | -     | -                                    |
+    /// | 4   | Reserved                           | -     | -                                    |
+    /// | 3   | Reserved                           | -     | -                                    |
+    /// | 2   | Reserved                           | -     | -                                    |
+    /// | 1   | Audio Inhibit                      | 1     | Suppress GDL 90 audio output         |
+    /// | 0   | CDTI OK                            | 1     | CDTI capability is operating         |
+    #[bitfield]
+    #[derive(BinRead, BinWrite, Debug, PartialEq)]
+    #[brw(little)]
+    pub struct ConfigurationByte1 {
+        pub cdti_ok: bool,
+        pub audio_inhibit: bool,
+        #[skip]
+        reserved_2345: B4,
+        pub audio_test: bool,
+        #[skip]
+        reserved_7: bool,
+    }
+
+    /// Initialization Configuration Byte 2, using bitfields.
+    ///
+    /// | Bit | Description                        | Value | Meaning                              |
+    /// |-----|------------------------------------|-------|--------------------------------------|
+    /// | 7   | Reserved                           | -     | -                                    |
+    /// | 6   | Reserved                           | -     | -                                    |
+    /// | 5   | Reserved                           | -     | -                                    |
+    /// | 4   | Reserved                           | -     | -                                    |
+    /// | 3   | Reserved                           | -     | -                                    |
+    /// | 2   | Reserved                           | -     | -                                    |
+    /// | 1   | CSA Audio Disable                  | 1     | Disable GDL 90 audible traffic alerts|
+    /// | 0   | CSA Disable                        | 1     | Disable CSA traffic alerting         |
+    #[bitfield]
+    #[derive(BinRead, BinWrite, Debug, PartialEq)]
+    #[brw(little)]
+    pub struct ConfigurationByte2 {
+        pub csa_disable: bool,
+        pub csa_audio_disable: bool,
+        #[skip]
+        reserved_234567: B6,
+    }
 }
 
-/// Initialization Configuration Byte 2, using bitfields.
-///
-/// | Bit | Description                        | Value | Meaning                              |
-/// |-----|------------------------------------|-------|--------------------------------------|
-/// | 7   | Reserved                           | -     | -                                    |
-/// | 6   | Reserved                           | -     | -                                    |
-/// | 5   | Reserved                           | -     | -                                    |
-/// | 4   | Reserved                           | -     | -                                    |
-/// | 3   | Reserved                           | -     | -                                    |
-/// | 2   | Reserved                           | -     | -                                    |
-/// | 1   | CSA Audio Disable                  | 1     | Disable GDL 90 audible traffic alerts|
-/// | 0   | CSA Disable                        | 1     | Disable CSA traffic alerting         |
-#[bitfield]
-#[derive(BinRead, Debug)]
-#[br(little)]
-pub struct ConfigurationByte2 {
-    pub csa_audio_disable: bool,
-    pub csa_disable: bool,
-    #[skip]
-    reserved_234567: B6,
+/// Message ID of the Initialization message.
+const INITIALIZATION_MESSAGE_ID: u8 = 0x02;
+
+/// Builds Initialization messages, every setting being disabled by default.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InitializationBuilder {
+    cdti_ok: bool,
+    audio_inhibit: bool,
+    audio_test: bool,
+    csa_disable: bool,
+    csa_audio_disable: bool,
+}
+
+impl InitializationBuilder {
+    /// Creates a builder with every setting disabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the CDTI capability is operating.
+    pub fn with_cdti_ok(mut self, cdti_ok: bool) -> Self {
+        self.cdti_ok = cdti_ok;
+        self
+    }
+
+    /// Whether the GDL 90 audio output should be suppressed.
+    pub fn with_audio_inhibit(mut self, audio_inhibit: bool) -> Self {
+        self.audio_inhibit = audio_inhibit;
+        self
+    }
+
+    /// Whether the GDL 90 should initiate an audio test.
+    pub fn with_audio_test(mut self, audio_test: bool) -> Self {
+        self.audio_test = audio_test;
+        self
+    }
+
+    /// Whether CSA traffic alerting should be disabled.
+    pub fn with_csa_disable(mut self, csa_disable: bool) -> Self {
+        self.csa_disable = csa_disable;
+        self
+    }
+
+    /// Whether the GDL 90 audible traffic alerts should be disabled.
+    pub fn with_csa_audio_disable(mut self, csa_audio_disable: bool) -> Self {
+        self.csa_audio_disable = csa_audio_disable;
+        self
+    }
+
+    /// Configuration Byte 1.
+    pub fn configuration_byte_1(&self) -> ConfigurationByte1 {
+        ConfigurationByte1::new()
+            .with_cdti_ok(self.cdti_ok)
+            .with_audio_inhibit(self.audio_inhibit)
+            .with_audio_test(self.audio_test)
+    }
+
+    /// Configuration Byte 2.
+    pub fn configuration_byte_2(&self) -> ConfigurationByte2 {
+        ConfigurationByte2::new()
+            .with_csa_disable(self.csa_disable)
+            .with_csa_audio_disable(self.csa_audio_disable)
+    }
+
+    /// Builds the [`Gdl90DatalinkMessage::Initialization`] message.
+    pub fn build(&self) -> Gdl90DatalinkMessage {
+        Gdl90DatalinkMessage::Initialization {
+            configuration_byte_1: self.configuration_byte_1(),
+            configuration_byte_2: self.configuration_byte_2(),
+        }
+    }
+
+    /// Builds the complete frame of the message, ready to be sent to the GDL 90, see [`frame`].
+    pub fn to_frame(&self) -> Vec<u8> {
+        let payload = [
+            self.configuration_byte_1().into_bytes()[0],
+            self.configuration_byte_2().into_bytes()[0],
+        ];
+        frame(INITIALIZATION_MESSAGE_ID, &payload)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use binrw::io::Cursor;
+
+    #[test]
+    fn configuration_bytes_round_trip() {
+        let parsed = ConfigurationByte1::read(&mut Cursor::new(b"\x41")).unwrap();
+        assert!(parsed.cdti_ok());
+        assert!(parsed.audio_test());
+        assert!(!parsed.audio_inhibit());
+
+        let mut output = Cursor::new(vec![]);
+        parsed.write(&mut output).unwrap();
+        assert_eq!(output.into_inner(), b"\x41");
+    }
+
+    #[test]
+    fn configuration_byte_2_bit_order() {
+        let parsed = ConfigurationByte2::read(&mut Cursor::new(b"\x01")).unwrap();
+        assert!(parsed.csa_disable());
+        assert!(!parsed.csa_audio_disable());
+
+        let parsed = ConfigurationByte2::read(&mut Cursor::new(b"\x02")).unwrap();
+        assert!(!parsed.csa_disable());
+        assert!(parsed.csa_audio_disable());
+    }
 
     #[test]
-    fn it_works() {}
+    fn builder_works() {
+        let builder = InitializationBuilder::new()
+            .with_cdti_ok(true)
+            .with_audio_inhibit(true)
+            .with_audio_test(true)
+            .with_csa_disable(true);
+        assert_eq!(builder.configuration_byte_1().into_bytes(), [0x43]);
+        assert_eq!(builder.configuration_byte_2().into_bytes(), [0x01]);
+
+        let frame = builder.to_frame();
+        let message = crate::read_raw(&frame).unwrap();
+        assert_eq!(message.message_data, builder.build());
+        assert_eq!(crate::write_raw(&message).unwrap(), frame);
+    }
 }
@@ -1,7 +1,51 @@
 //! Custom types in order to parse GDL90 datalink messages.
 
+pub mod foreflight;
 pub mod heartbeat;
+pub mod height_above_terrain;
+pub mod icao;
 pub mod initialization;
+pub mod n_number;
 pub mod ownship_geometric_altitude;
 pub mod report;
+#[cfg(feature = "stratux")]
+pub mod stratux;
+pub mod uat_adsb;
 pub mod uplink_data;
+
+/// Reads `len` (up to 32) bits starting at bit `offset` of `data`, most significant bit first.
+pub(crate) fn read_bits(data: &[u8], offset: usize, len: usize) -> u32 {
+    (offset..offset + len).fold(0, |acc, pos| {
+        (acc << 1) | ((data[pos / 8] >> (7 - pos % 8)) & 1) as u32
+    })
+}
+
+/// Rounds `x` half away from zero, like `f32::round` which requires `std`.
+pub(crate) fn round(x: f32) -> f32 {
+    // larger values are already integral
+    if x.is_nan() || x.abs() >= 8_388_608.0 {
+        return x;
+    }
+    let truncated = x as i32 as f32;
+    match x - truncated {
+        diff if diff >= 0.5 => truncated + 1.0,
+        diff if diff <= -0.5 => truncated - 1.0,
+        _ => truncated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_works() {
+        for x in [
+            0.0, 0.4, 0.5, 1.49, 2.5, -0.4, -0.5, -2.5, -2.6, 1234.5, 1e10, -1e10,
+        ] {
+            assert_eq!(round(x), x.round(), "{x}");
+        }
+        assert!(round(f32::NAN).is_nan());
+        assert_eq!(round(f32::INFINITY), f32::INFINITY);
+    }
+}
@@ -0,0 +1,198 @@
+//! Conversion between US civil registrations (N-numbers) and ICAO addresses.
+//!
+//! The FAA assigns the `A00001` to `ADF7C7` block in registration order: `N1`, `N1A`, `N1AA`,
+//! `N1AB`, .., `N1Z`, `N1ZZ`, `N10`, `N10A`, .., `N99999`. Each address can therefore be computed
+//! from its registration (and back) without any lookup table.
+//!
+//! A registration is `N` followed by up to 5 characters:
+//! - a first digit from 1 to 9;
+//! - up to 4 more digits;
+//! - up to 2 letters (`I` and `O` excluded), only at the end and within the 5 characters.
+//!
+//! ## Example
+//! ```
+//! use gdl90::types::icao::IcaoAddress;
+//!
+//! let address = IcaoAddress::from_n_number("N905WN").unwrap();
+//! assert_eq!(address.to_string(), "AC83C0");
+//! assert_eq!(address.n_number().as_deref(), Some("N905WN"));
+//! ```
+
+use alloc::string::String;
+
+use super::icao::IcaoAddress;
+
+/// Letters used in registrations, `I` and `O` being excluded.
+const CHARSET: &[u8; 24] = b"ABCDEFGHJKLMNPQRSTUVWXYZ";
+
+/// First address of the block, `N1`.
+const FIRST_ADDRESS: u32 = 0xA00001;
+
+/// Last address of the block, `N99999`.
+const LAST_ADDRESS: u32 = 0xADF7C7;
+
+/// Number of letter suffixes (none, `A`, `AA`, `AB`, .., `ZZ`) after a digit.
+const SUFFIX_SIZE: u32 = 1 + CHARSET.len() as u32 * (1 + CHARSET.len() as u32);
+
+/// Addresses used by the registrations following the 4th digit (none, a letter or a digit).
+const BUCKET4_SIZE: u32 = 1 + CHARSET.len() as u32 + 10;
+
+/// Addresses used by the registrations following the 3rd digit.
+const BUCKET3_SIZE: u32 = 10 * BUCKET4_SIZE + SUFFIX_SIZE;
+
+/// Addresses used by the registrations following the 2nd digit.
+const BUCKET2_SIZE: u32 = 10 * BUCKET3_SIZE + SUFFIX_SIZE;
+
+/// Addresses used by the registrations following the 1st digit.
+const BUCKET1_SIZE: u32 = 10 * BUCKET2_SIZE + SUFFIX_SIZE;
+
+impl IcaoAddress {
+    /// Returns the address of a US civil registration (eg. `N905WN`, case insensitive),
+    /// or `None` if it is not a valid N-number.
+    pub fn from_n_number(n_number: &str) -> Option<Self> {
+        let n_number = n_number.to_ascii_uppercase();
+        let chars = n_number.strip_prefix('N')?.as_bytes();
+        if chars.is_empty() || chars.len() > 5 {
+            return None;
+        }
+
+        let digit = |c: u8| c.is_ascii_digit().then(|| (c - b'0') as u32);
+        let letter = |c: u8| CHARSET.iter().position(|l| *l == c).map(|i| i as u32);
+
+        let first = digit(chars[0]).filter(|d| *d != 0)?;
+        let mut offset = (first - 1) * BUCKET1_SIZE;
+
+        for (i, &c) in chars.iter().enumerate().skip(1) {
+            if i == 4 {
+                // last character, either a letter or a digit
+                offset += match (letter(c), digit(c)) {
+                    (Some(l), _) => l + 1,
+                    (_, Some(d)) => CHARSET.len() as u32 + d + 1,
+                    _ => return None,
+                };
+                break;
+            }
+
+            if let Some(d) = digit(c) {
+                let bucket = [BUCKET2_SIZE, BUCKET3_SIZE, BUCKET4_SIZE][i - 1];
+                offset += SUFFIX_SIZE + d * bucket;
+                continue;
+            }
+
+            // letter suffix, ends the registration
+            let suffix = &chars[i..];
+            let first = letter(suffix[0])?;
+            offset += first * (CHARSET.len() as u32 + 1) + 1;
+            match suffix {
+                [_] => {}
+                [_, second] if i < 4 => offset += letter(*second)? + 1,
+                _ => return None,
+            }
+            break;
+        }
+
+        Self::new(FIRST_ADDRESS + offset)
+    }
+
+    /// Returns the US civil registration of the address (eg. `N905WN`),
+    /// or `None` if it is outside of the `A00001` to `ADF7C7` block.
+    pub fn n_number(self) -> Option<String> {
+        if !(FIRST_ADDRESS..=LAST_ADDRESS).contains(&self.value()) {
+            return None;
+        }
+
+        let mut output = String::from("N");
+        let mut rest = self.value() - FIRST_ADDRESS;
+
+        output.push(char::from(b'1' + (rest / BUCKET1_SIZE) as u8));
+        rest %= BUCKET1_SIZE;
+
+        for bucket in [BUCKET2_SIZE, BUCKET3_SIZE, BUCKET4_SIZE] {
+            if rest < SUFFIX_SIZE {
+                push_suffix(&mut output, rest);
+                return Some(output);
+            }
+            rest -= SUFFIX_SIZE;
+            output.push(char::from(b'0' + (rest / bucket) as u8));
+            rest %= bucket;
+        }
+
+        // last character, either a letter or a digit
+        match rest as usize {
+            0 => {}
+            n if n <= CHARSET.len() => output.push(char::from(CHARSET[n - 1])),
+            n => output.push(char::from(b'0' + (n - CHARSET.len() - 1) as u8)),
+        }
+        Some(output)
+    }
+}
+
+/// Appends the letter suffix at `offset` (0 being no suffix, then `A`, `AA`, `AB`, ..).
+fn push_suffix(output: &mut String, offset: u32) {
+    if offset == 0 {
+        return;
+    }
+    let offset = (offset - 1) as usize;
+    output.push(char::from(CHARSET[offset / (CHARSET.len() + 1)]));
+    if let Some(second) = (offset % (CHARSET.len() + 1)).checked_sub(1) {
+        output.push(char::from(CHARSET[second]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_address(n_number: &str) -> Option<u32> {
+        IcaoAddress::from_n_number(n_number).map(IcaoAddress::value)
+    }
+
+    #[test]
+    fn known_registrations_work() {
+        let known = [
+            ("N1", 0xA00001),
+            ("N1A", 0xA00002),
+            ("N1AA", 0xA00003),
+            ("N1ZZ", 0xA00259),
+            ("N10", 0xA0025A),
+            ("N100", 0xA004B3),
+            ("N1000", 0xA0070C),
+            ("N1000A", 0xA0070D),
+            ("N10000", 0xA00725),
+            ("N905WN", 0xAC83C0),
+            ("N99999", 0xADF7C7),
+        ];
+        for (n_number, address) in known {
+            assert_eq!(to_address(n_number), Some(address), "{n_number}");
+            let address = IcaoAddress::new(address).unwrap();
+            assert_eq!(address.n_number().as_deref(), Some(n_number));
+        }
+        assert_eq!(to_address("n905wn"), Some(0xAC83C0));
+    }
+
+    #[test]
+    fn invalid_registrations_fail() {
+        for n_number in [
+            "", "N", "1", "N0", "N01", "NA", "N1I", "N1O", "N1AAA", "N1A1", "N12345A", "N1234AB",
+            "N123456", "N-1", "G1",
+        ] {
+            assert_eq!(to_address(n_number), None, "{n_number}");
+        }
+
+        for address in [0x000000, 0xA00000, 0xADF7C8, 0xAFFFFF] {
+            assert_eq!(IcaoAddress::new(address).unwrap().n_number(), None);
+        }
+    }
+
+    #[test]
+    fn round_trip_works() {
+        let mut previous = String::new();
+        for address in FIRST_ADDRESS..=LAST_ADDRESS {
+            let address = IcaoAddress::new(address).unwrap();
+            let n_number = address.n_number().unwrap();
+            assert_eq!(IcaoAddress::from_n_number(&n_number), Some(address));
+            assert_ne!(n_number, previous);
+            previous = n_number;
+        }
+    }
+}
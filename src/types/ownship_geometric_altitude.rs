@@ -1,75 +1,229 @@
-//! GDL90 Ownship Geometric Altitude custom types.
+//! GDL90 Ownship Geometric Altitude custom types. 560-1058-00 Rev A - ref 3.8
 
-use binrw::BinRead;
-use modular_bitfield::{bitfield, Specifier};
+use binrw::binrw;
 
-/// Vertical Metrics wrapper, using bitfields.
-#[bitfield]
-#[derive(BinRead, Debug)]
-#[br(little)]
+/// Ownship Geometric Altitude resolution, in feet.
+pub const GDL90_GEO_ALTITUDE_FACTOR: i32 = 5;
+
+/// VFOM value meaning that the VFOM is larger than 32766 meters.
+const VFOM_SATURATED: u16 = 0x7FFE;
+
+/// VFOM value meaning that the VFOM is not available.
+const VFOM_UNAVAILABLE: u16 = 0x7FFF;
+
+/// Converts an altitude in feet to its 5 ft. resolution value, rounding to the nearest
+/// value and saturating to the `i16` range.
+pub(crate) fn encode_geo_altitude(feet: i32) -> i16 {
+    let half = GDL90_GEO_ALTITUDE_FACTOR / 2;
+    let rounded = if feet < 0 { feet - half } else { feet + half } / GDL90_GEO_ALTITUDE_FACTOR;
+    rounded.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+}
+
+/// Vertical Metrics, transmitted most significant byte first.
+///
+/// | Bit  | Description                       |
+/// |------|-----------------------------------|
+/// | 15   | Vertical Warning indicator        |
+/// | 14-0 | Vertical Figure of Merit, see [`Vfom`] |
+#[binrw]
+#[brw(big)]
+#[br(map = Self::from_raw)]
+#[bw(map = |x: &Self| x.to_raw())]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub struct VerticalMetrics {
-    pub vertical_figure_of_merit: Vfom,
-    pub vertical_warning_indicator: bool,
+    vertical_warning_indicator: bool,
+    vertical_figure_of_merit: Vfom,
+}
+
+impl Default for VerticalMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VerticalMetrics {
+    /// Creates metrics without warning and with a VFOM of 0 meters.
+    pub const fn new() -> Self {
+        Self {
+            vertical_warning_indicator: false,
+            vertical_figure_of_merit: Vfom::Available(0),
+        }
+    }
+
+    /// Whether the GDL 90 considers the vertical position unreliable (eg. the VFOM is too large).
+    pub fn vertical_warning_indicator(&self) -> bool {
+        self.vertical_warning_indicator
+    }
+
+    /// Sets the Vertical Warning indicator.
+    pub fn set_vertical_warning_indicator(&mut self, warning: bool) {
+        self.vertical_warning_indicator = warning;
+    }
+
+    /// Returns a copy of the metrics with the Vertical Warning indicator set.
+    pub fn with_vertical_warning_indicator(mut self, warning: bool) -> Self {
+        self.set_vertical_warning_indicator(warning);
+        self
+    }
+
+    /// Vertical Figure of Merit.
+    pub fn vertical_figure_of_merit(&self) -> Vfom {
+        self.vertical_figure_of_merit
+    }
+
+    /// Sets the Vertical Figure of Merit.
+    pub fn set_vertical_figure_of_merit(&mut self, vfom: Vfom) {
+        self.vertical_figure_of_merit = vfom;
+    }
+
+    /// Returns a copy of the metrics with the Vertical Figure of Merit set.
+    pub fn with_vertical_figure_of_merit(mut self, vfom: Vfom) -> Self {
+        self.set_vertical_figure_of_merit(vfom);
+        self
+    }
+
+    /// Decodes the metrics from their 16 bits.
+    pub fn from_raw(raw: u16) -> Self {
+        Self {
+            vertical_warning_indicator: raw & 0x8000 != 0,
+            vertical_figure_of_merit: Vfom::from_raw(raw),
+        }
+    }
+
+    /// Encodes the metrics to their 16 bits, values which do not fit being saturated.
+    pub fn to_raw(&self) -> u16 {
+        ((self.vertical_warning_indicator as u16) << 15) | self.vertical_figure_of_merit.to_raw()
+    }
 }
 
 /// Vertical Figure of Merit (VFOM).
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum Vfom {
+    /// VFOM in meters, up to 32765.
     Available(u16),
+
+    /// VFOM larger than 32766 meters.
+    Saturated,
+
     Unavailable,
 }
 
-impl Specifier for Vfom {
-    const BITS: usize = 15;
-    type Bytes = u16;
-    type InOut = Vfom;
+impl Vfom {
+    /// Creates a VFOM from meters, saturating values of 32766 meters and above.
+    pub fn from_meters(meters: u32) -> Self {
+        if meters >= VFOM_SATURATED as u32 {
+            Vfom::Saturated
+        } else {
+            Vfom::Available(meters as u16)
+        }
+    }
+
+    /// VFOM in meters, `None` if it is not available or saturated.
+    pub fn meters(self) -> Option<u16> {
+        match self {
+            Vfom::Available(x) => Some(x),
+            _ => None,
+        }
+    }
 
-    fn into_bytes(input: Self::InOut) -> Result<Self::Bytes, modular_bitfield::error::OutOfBounds> {
-        unimplemented!()
+    /// Decodes the VFOM from the 15 least significant bits of `raw`.
+    fn from_raw(raw: u16) -> Self {
+        match raw & 0x7FFF {
+            VFOM_UNAVAILABLE => Vfom::Unavailable,
+            VFOM_SATURATED => Vfom::Saturated,
+            x => Vfom::Available(x),
+        }
     }
 
-    fn from_bytes(
-        input: Self::Bytes,
-    ) -> Result<Self::InOut, modular_bitfield::error::InvalidBitPattern<Self::Bytes>> {
-        let masked = input & 0x7FFF;
-        let res = match masked {
-            0x7FFF => Vfom::Unavailable,
-            // 0x7FFE => ??? FIXME: HANDLE SATURATION?
-            _ => Vfom::Available(masked),
-        };
-        Ok(res)
+    /// Encodes the VFOM to 15 bits, saturating values which do not fit.
+    fn to_raw(self) -> u16 {
+        match self {
+            Vfom::Available(x) => x.min(VFOM_SATURATED),
+            Vfom::Saturated => VFOM_SATURATED,
+            Vfom::Unavailable => VFOM_UNAVAILABLE,
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Cursor;
+    use binrw::io::Cursor;
+    use binrw::{BinRead, BinWrite};
 
     #[test]
-    fn vfom_bitfield_works() {
-        assert_eq!(Vfom::from_bytes(0xFFFF).unwrap(), Vfom::Unavailable);
-        //assert_eq!(Vfom::from_bytes(0x7FFE).unwrap(), Vfom::Available(40000)); // FIXME SATURATION?
-        assert_eq!(Vfom::from_bytes(0x000A).unwrap(), Vfom::Available(10));
-        assert_eq!(Vfom::from_bytes(0x8032).unwrap(), Vfom::Available(50));
+    fn vfom_raw_works() {
+        assert_eq!(Vfom::from_raw(0xFFFF), Vfom::Unavailable);
+        assert_eq!(Vfom::from_raw(0x7FFE), Vfom::Saturated);
+        assert_eq!(Vfom::from_raw(0x000A), Vfom::Available(10));
+        assert_eq!(Vfom::from_raw(0x8032), Vfom::Available(50));
+
+        assert_eq!(Vfom::Unavailable.to_raw(), 0x7FFF);
+        assert_eq!(Vfom::Saturated.to_raw(), 0x7FFE);
+        assert_eq!(Vfom::Available(10).to_raw(), 0x000A);
+        // values which do not fit are saturated
+        assert_eq!(Vfom::Available(0x7FFE).to_raw(), 0x7FFE);
+        assert_eq!(Vfom::Available(0x8000).to_raw(), 0x7FFE);
+    }
+
+    #[test]
+    fn vfom_meters_works() {
+        assert_eq!(Vfom::from_meters(32765), Vfom::Available(32765));
+        assert_eq!(Vfom::from_meters(32766), Vfom::Saturated);
+        assert_eq!(Vfom::from_meters(100000), Vfom::Saturated);
+        assert_eq!(Vfom::Available(12).meters(), Some(12));
+        assert_eq!(Vfom::Saturated.meters(), None);
+        assert_eq!(Vfom::Unavailable.meters(), None);
     }
 
     #[test]
     fn vertical_metrics_works() {
         let parsed = VerticalMetrics::read(&mut Cursor::new(b"\xFF\xFF")).unwrap();
-        assert_eq!(parsed.vertical_warning_indicator(), true);
+        assert!(parsed.vertical_warning_indicator());
         assert_eq!(parsed.vertical_figure_of_merit(), Vfom::Unavailable);
 
-        let parsed = VerticalMetrics::read(&mut Cursor::new(b"\xFE\x7F")).unwrap();
-        assert_eq!(parsed.vertical_warning_indicator(), false);
-        // assert_eq!(parsed.vertical_figure_of_merit(), Vfom::Available(40000)); // FIXME SATURATION?
+        let parsed = VerticalMetrics::read(&mut Cursor::new(b"\x7F\xFE")).unwrap();
+        assert!(!parsed.vertical_warning_indicator());
+        assert_eq!(parsed.vertical_figure_of_merit(), Vfom::Saturated);
 
-        let parsed = VerticalMetrics::read(&mut Cursor::new(b"\x0A\x00")).unwrap();
-        assert_eq!(parsed.vertical_warning_indicator(), false);
+        let parsed = VerticalMetrics::read(&mut Cursor::new(b"\x00\x0A")).unwrap();
+        assert!(!parsed.vertical_warning_indicator());
         assert_eq!(parsed.vertical_figure_of_merit(), Vfom::Available(10));
 
-        let parsed = VerticalMetrics::read(&mut Cursor::new(b"\x32\x80")).unwrap();
-        assert_eq!(parsed.vertical_warning_indicator(), true);
+        let parsed = VerticalMetrics::read(&mut Cursor::new(b"\x80\x32")).unwrap();
+        assert!(parsed.vertical_warning_indicator());
         assert_eq!(parsed.vertical_figure_of_merit(), Vfom::Available(50));
     }
+
+    #[test]
+    fn vertical_metrics_write_works() {
+        let write = |metrics: VerticalMetrics| {
+            let mut output = Cursor::new(vec![]);
+            metrics.write(&mut output).unwrap();
+            output.into_inner()
+        };
+
+        let metrics = VerticalMetrics::new()
+            .with_vertical_warning_indicator(true)
+            .with_vertical_figure_of_merit(Vfom::Available(50));
+        assert_eq!(write(metrics), [0x80, 0x32]);
+
+        let metrics = metrics.with_vertical_figure_of_merit(Vfom::Saturated);
+        assert_eq!(write(metrics), [0xFF, 0xFE]);
+
+        // out of range values are saturated when written
+        let metrics = VerticalMetrics::new().with_vertical_figure_of_merit(Vfom::Available(0xFFFF));
+        assert_eq!(write(metrics), [0x7F, 0xFE]);
+    }
+
+    #[test]
+    fn geo_altitude_encoding_works() {
+        assert_eq!(encode_geo_altitude(1010), 202);
+        assert_eq!(encode_geo_altitude(1012), 202);
+        assert_eq!(encode_geo_altitude(1013), 203);
+        assert_eq!(encode_geo_altitude(-1250), -250);
+        assert_eq!(encode_geo_altitude(-1253), -251);
+        assert_eq!(encode_geo_altitude(1_000_000), i16::MAX);
+        assert_eq!(encode_geo_altitude(-1_000_000), i16::MIN);
+    }
 }
@@ -1,58 +1,219 @@
 //! GDL90 Report message and types (for Ownship and Traffic). 560-1058-00 Rev A - ref 3.5.1.x
 
-use binrw::BinRead;
+use alloc::string::{String, ToString};
+
+use binrw::{BinRead, BinWrite};
+
+use super::icao::IcaoAddress;
+use super::round;
 use modular_bitfield::{
     bitfield,
-    prelude::{B24, B4, B8},
+    error::OutOfBounds,
+    prelude::{B16, B24, B4, B8},
     BitfieldSpecifier, Specifier,
 };
 
-/// Common Report data structure.
-#[bitfield]
-#[derive(BinRead, Debug)]
-#[br(little)]
-pub struct Report {
-    /// Traffic Alert Status.
-    pub traffic_alert_status: TrafficAlert,
+pub use self::bitfields::Report;
+
+// Lints triggered by the `modular_bitfield` expansion.
+#[allow(unused_parens, clippy::identity_op, clippy::new_without_default)]
+mod bitfields {
+    use super::*;
+
+    /// Common Report data structure.
+    #[bitfield]
+    #[derive(BinRead, BinWrite, Debug, PartialEq)]
+    #[brw(little)]
+    pub struct Report {
+        /// Address Type, see [`Report::participant`].
+        pub(super) address_type: B4,
+
+        /// Traffic Alert Status.
+        pub traffic_alert_status: TrafficAlert,
+
+        /// Participant Address, see [`Report::participant`].
+        pub(super) participant_address: B24,
+
+        /// Latitude.
+        pub latitude: Cord,
+
+        /// Longitude.
+        pub longitude: Cord,
+
+        /// Altitude (12 bits) followed by the Miscellaneous Indicators (4 bits),
+        /// see [`Report::altitude`] and [`Report::misc_indicators`].
+        pub(super) altitude_misc: B16,
+
+        /// Navigation Accuracy Category for Position.
+        pub nacp: Nacp,
+
+        /// Navigation Integrity Category.
+        pub nic: Nic,
 
-    /// Participant.
-    pub participant: Participant,
+        // Velocity.
+        pub velocity: Velocity,
 
-    /// Latitude.
-    pub latitude: Cord,
+        /// Track/Heading angle, its type being held by the Miscellaneous Indicators,
+        /// see [`Report::track_heading`].
+        pub(super) track_heading_raw: B8,
 
-    /// Longitude.
-    pub longitude: Cord,
+        /// Emitter Category.
+        pub emmiter_cattegory: EmmiterCategory,
 
-    /// Miscellaneous indicator.
-    //pub misc_indicators: B4, // FIXME: MiscIndicator, // altitude gets all the bytes?
+        /// Call Sign.
+        pub call_sign: CallSignType,
+
+        /// Spare (reserved for future use).
+        pub reserved: B4,
+
+        /// Emergency/Priority Code.
+        pub emergency_priority_code: EmergencyPriorityCodeCategory,
+    }
+}
+
+impl Report {
+    /// Participant (Address Type and Address).
+    pub fn participant(&self) -> Participant {
+        let [a0, a1, a2, _] = self.participant_address().to_le_bytes();
+        Participant {
+            address_type: AddressType::from(self.address_type()),
+            participant_address: IcaoAddress::from_be_bytes([a0, a1, a2]),
+        }
+    }
+
+    /// Sets the participant.
+    pub fn set_participant(&mut self, participant: Participant) {
+        let [a0, a1, a2] = participant.participant_address.to_be_bytes();
+        self.set_address_type(participant.address_type.into());
+        self.set_participant_address(u32::from_le_bytes([a0, a1, a2, 0]));
+    }
+
+    /// Returns a copy of the report with the participant set.
+    pub fn with_participant(mut self, participant: Participant) -> Self {
+        self.set_participant(participant);
+        self
+    }
 
     /// Altitude.
-    pub altitude: Altitude,
+    pub fn altitude(&self) -> Altitude {
+        let raw = self.altitude_misc_be() >> 4;
+        Altitude::from_bytes(raw).unwrap_or(Altitude::InvalidOrUnknown)
+    }
+
+    /// Sets the altitude, panicking if it is out of bounds.
+    pub fn set_altitude(&mut self, altitude: Altitude) {
+        self.set_altitude_checked(altitude)
+            .expect("value out of bounds for field Report.altitude")
+    }
+
+    /// Sets the altitude, returning an error if it is out of bounds.
+    pub fn set_altitude_checked(&mut self, altitude: Altitude) -> Result<(), OutOfBounds> {
+        let raw = Altitude::into_bytes(altitude)?;
+        self.set_altitude_misc_be((raw << 4) | (self.altitude_misc_be() & 0x000F));
+        Ok(())
+    }
+
+    /// Returns a copy of the report with the altitude set, panicking if it is out of bounds.
+    pub fn with_altitude(mut self, altitude: Altitude) -> Self {
+        self.set_altitude(altitude);
+        self
+    }
+
+    /// Miscellaneous Indicators.
+    pub fn misc_indicators(&self) -> MiscIndicator {
+        let raw = (self.altitude_misc_be() & 0x000F) as u8;
+        MiscIndicator::from_bytes(raw).unwrap_or_default()
+    }
 
-    /// Navigation Accuracy Category for Position. TODO: better type?
-    pub nacp: B4,
+    /// Sets the Miscellaneous Indicators.
+    pub fn set_misc_indicators(&mut self, misc_indicators: MiscIndicator) {
+        let raw = MiscIndicator::into_bytes(misc_indicators).unwrap_or_default() as u16;
+        self.set_altitude_misc_be((self.altitude_misc_be() & 0xFFF0) | raw);
+    }
+
+    /// Returns a copy of the report with the Miscellaneous Indicators set.
+    pub fn with_misc_indicators(mut self, misc_indicators: MiscIndicator) -> Self {
+        self.set_misc_indicators(misc_indicators);
+        self
+    }
+
+    /// Track/Heading, whose type comes from the Miscellaneous Indicators.
+    pub fn track_heading(&self) -> TrackHeading {
+        TrackHeading::from_raw(self.track_heading_raw(), self.misc_indicators().track_type)
+    }
+
+    /// Sets the Track/Heading angle, and its type in the Miscellaneous Indicators.
+    pub fn set_track_heading(&mut self, track_heading: TrackHeading) {
+        self.set_track_heading_raw(track_heading.to_raw());
+        self.set_misc_indicators(MiscIndicator {
+            track_type: track_heading.kind,
+            ..self.misc_indicators()
+        });
+    }
+
+    /// Returns a copy of the report with the Track/Heading set.
+    pub fn with_track_heading(mut self, track_heading: TrackHeading) -> Self {
+        self.set_track_heading(track_heading);
+        self
+    }
+
+    /// Altitude and Miscellaneous Indicators as transmitted (`ddd` `m`).
+    fn altitude_misc_be(&self) -> u16 {
+        u16::swap_bytes(self.altitude_misc())
+    }
+
+    fn set_altitude_misc_be(&mut self, value: u16) {
+        self.set_altitude_misc(u16::swap_bytes(value));
+    }
+}
 
-    /// Navigation Integrity Category. TODO: better type?
-    pub nic: B4,
+/// Resolution of the Track/Heading field, in degrees.
+const GDL90_TRACK_HEADING_RESOLUTION: f32 = 360.0 / 256.0;
 
-    // Velocity.
-    pub velocity: Velocity,
+/// Track or heading of a report, see [`Report::track_heading`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TrackHeading {
+    /// Whether this is a track or a heading, and its reference.
+    pub kind: TrackType,
 
-    /// Track/Heading.
-    pub track_heading: B8,
+    /// Angle in degrees (0-360), clockwise from north.
+    pub degrees: f32,
+}
 
-    /// Emitter Category.
-    pub emmiter_cattegory: EmmiterCategory,
+impl TrackHeading {
+    /// Creates a track/heading of `degrees`, clockwise from north in the 0 to 360 range.
+    /// Other values are wrapped around 360 degrees when encoded, see [`TrackHeading::to_raw`].
+    pub fn new(kind: TrackType, degrees: f32) -> Self {
+        Self { kind, degrees }
+    }
 
-    /// Call Sign.
-    pub call_sign: CallSignType,
+    /// Angle in degrees, `None` if the track/heading is not valid.
+    pub fn angle(&self) -> Option<f32> {
+        match self.kind {
+            TrackType::NotValid => None,
+            _ => Some(self.degrees),
+        }
+    }
 
-    /// Emergency/Priority Code.
-    pub emergency_priority_code: EmergencyPriorityCodeCategory,
+    /// Decodes the 8-bit angle (360/256 degrees resolution).
+    pub fn from_raw(raw: u8, kind: TrackType) -> Self {
+        Self {
+            kind,
+            degrees: raw as f32 * GDL90_TRACK_HEADING_RESOLUTION,
+        }
+    }
 
-    /// Spare (reserved for future use).
-    pub reserved: B4,
+    /// Encodes the angle as 8 bits (360/256 degrees resolution), wrapping around 360 degrees.
+    pub fn to_raw(&self) -> u8 {
+        let degrees = self.degrees % 360.0;
+        let degrees = if degrees < 0.0 {
+            degrees + 360.0
+        } else {
+            degrees
+        };
+        let raw = round(degrees / GDL90_TRACK_HEADING_RESOLUTION);
+        (raw as u32 % 256) as u8
+    }
 }
 
 /// 4-bit field which indicates whether CSA has identified this target with an alert.
@@ -78,7 +239,7 @@ pub enum TrafficAlert {
 }
 
 /// 4-bit field which describes the type of address conveyed in the [`Participant::participant_address`] field.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum AddressType {
     ADSBWithICAOAddress,
     ADSBWithSelfAssignedAddress ,
@@ -89,6 +250,139 @@ pub enum AddressType {
     Reserved,
 }
 
+/// Meters per nautical mile.
+const METERS_PER_NM: f32 = 1852.0;
+
+/// 4-bit field which describes the Navigation Accuracy Category for Position (NACp).
+///
+/// Each category bounds the Estimated Position Uncertainty (EPU) of the reported position.
+#[derive(BitfieldSpecifier, Debug, PartialEq, Clone, Copy)]
+#[bits = 4]
+pub enum Nacp {
+    Unknown,
+    /// EPU < 10.0 NM.
+    Epu10Nm,
+    /// EPU < 4.0 NM.
+    Epu4Nm,
+    /// EPU < 2.0 NM.
+    Epu2Nm,
+    /// EPU < 1.0 NM.
+    Epu1Nm,
+    /// EPU < 0.5 NM.
+    Epu0p5Nm,
+    /// EPU < 0.3 NM.
+    Epu0p3Nm,
+    /// EPU < 0.1 NM.
+    Epu0p1Nm,
+    /// EPU < 0.05 NM.
+    Epu0p05Nm,
+    /// HFOM < 30 m and VFOM < 45 m.
+    Epu30M,
+    /// HFOM < 10 m and VFOM < 15 m.
+    Epu10M,
+    /// HFOM < 3 m and VFOM < 4 m.
+    Epu3M,
+    Reserved0,
+    Reserved1,
+    Reserved2,
+    Reserved3,
+}
+
+impl Nacp {
+    /// Horizontal accuracy bound (EPU) in meters, `None` if unknown.
+    pub fn epu_meters(&self) -> Option<f32> {
+        match self {
+            Self::Epu10Nm => Some(10.0 * METERS_PER_NM),
+            Self::Epu4Nm => Some(4.0 * METERS_PER_NM),
+            Self::Epu2Nm => Some(2.0 * METERS_PER_NM),
+            Self::Epu1Nm => Some(1.0 * METERS_PER_NM),
+            Self::Epu0p5Nm => Some(0.5 * METERS_PER_NM),
+            Self::Epu0p3Nm => Some(0.3 * METERS_PER_NM),
+            Self::Epu0p1Nm => Some(0.1 * METERS_PER_NM),
+            Self::Epu0p05Nm => Some(0.05 * METERS_PER_NM),
+            Self::Epu30M => Some(30.0),
+            Self::Epu10M => Some(10.0),
+            Self::Epu3M => Some(3.0),
+            _ => None,
+        }
+    }
+
+    /// Vertical accuracy bound (VFOM) in meters, only defined by the highest categories.
+    pub fn vertical_epu_meters(&self) -> Option<f32> {
+        match self {
+            Self::Epu30M => Some(45.0),
+            Self::Epu10M => Some(15.0),
+            Self::Epu3M => Some(4.0),
+            _ => None,
+        }
+    }
+}
+
+/// 4-bit field which describes the Navigation Integrity Category (NIC).
+///
+/// Each category bounds the horizontal containment radius (Rc) of the reported position.
+#[derive(BitfieldSpecifier, Debug, PartialEq, Clone, Copy)]
+#[bits = 4]
+pub enum Nic {
+    Unknown,
+    /// Rc < 20.0 NM.
+    Rc20Nm,
+    /// Rc < 8.0 NM.
+    Rc8Nm,
+    /// Rc < 4.0 NM.
+    Rc4Nm,
+    /// Rc < 2.0 NM.
+    Rc2Nm,
+    /// Rc < 1.0 NM.
+    Rc1Nm,
+    /// Rc < 0.6 NM.
+    Rc0p6Nm,
+    /// Rc < 0.2 NM.
+    Rc0p2Nm,
+    /// Rc < 0.1 NM.
+    Rc0p1Nm,
+    /// HPL < 75 m and VPL < 112 m.
+    Rc75M,
+    /// HPL < 25 m and VPL < 37.5 m.
+    Rc25M,
+    /// HPL < 7.5 m and VPL < 11 m.
+    Rc7p5M,
+    Reserved0,
+    Reserved1,
+    Reserved2,
+    Reserved3,
+}
+
+impl Nic {
+    /// Horizontal containment radius (Rc) in meters, `None` if unknown.
+    pub fn containment_radius_meters(&self) -> Option<f32> {
+        match self {
+            Self::Rc20Nm => Some(20.0 * METERS_PER_NM),
+            Self::Rc8Nm => Some(8.0 * METERS_PER_NM),
+            Self::Rc4Nm => Some(4.0 * METERS_PER_NM),
+            Self::Rc2Nm => Some(2.0 * METERS_PER_NM),
+            Self::Rc1Nm => Some(1.0 * METERS_PER_NM),
+            Self::Rc0p6Nm => Some(0.6 * METERS_PER_NM),
+            Self::Rc0p2Nm => Some(0.2 * METERS_PER_NM),
+            Self::Rc0p1Nm => Some(0.1 * METERS_PER_NM),
+            Self::Rc75M => Some(75.0),
+            Self::Rc25M => Some(25.0),
+            Self::Rc7p5M => Some(7.5),
+            _ => None,
+        }
+    }
+
+    /// Vertical protection limit (VPL) in meters, only defined by the highest categories.
+    pub fn vertical_protection_limit_meters(&self) -> Option<f32> {
+        match self {
+            Self::Rc75M => Some(112.0),
+            Self::Rc25M => Some(37.5),
+            Self::Rc7p5M => Some(11.0),
+            _ => None,
+        }
+    }
+}
+
 /// 8-bit field which describes the Emmiter Category.
 #[derive(BitfieldSpecifier, Debug, PartialEq)]
 #[bits = 8]
@@ -158,45 +452,62 @@ pub enum EmergencyPriorityCodeCategory {
 }
 
 /// 4-bit field which describes the miscellaneous indicator bits that apply to the Traffic Report field.
-#[derive(Debug, PartialEq)]
-pub enum MiscIndicator {
-    // FIXME: how to get non set bits? bit masking?
-    TrackHeadingNotValid,
-    TrackHeadingTrueTrackAngle,
-    TrackHeadingMagnetic,
-    TrackHeadingTrue,
-    ReportUpdated,
-    ReportExtrapolated,
-    OnGround,
-    Airborne,
+///
+/// | Bit   | Name                                                  |
+/// |-------|-------------------------------------------------------|
+/// | 0-1   | Track/Heading type, see [`TrackType`]                 |
+/// | 2     | 0 = Report Updated, 1 = Report Extrapolated           |
+/// | 3     | 0 = On Ground, 1 = Airborne                           |
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct MiscIndicator {
+    /// Type of the Track/Heading field.
+    pub track_type: TrackType,
+
+    /// Whether the report was extrapolated (`false` means updated).
+    pub extrapolated: bool,
+
+    /// Whether the target is airborne (`false` means on ground).
+    pub airborne: bool,
 }
 
-#[derive(PartialEq, Debug)]
+/// Type of the Track/Heading field, see [`MiscIndicator`].
+#[derive(BitfieldSpecifier, Debug, PartialEq, Clone, Copy, Default)]
+#[bits = 2]
+pub enum TrackType {
+    #[default]
+    NotValid,
+    TrueTrackAngle,
+    MagneticHeading,
+    TrueHeading,
+}
 
+/// Participant of a report, see [`Report::participant`].
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub struct Participant {
     /// Address Type.
     pub address_type: AddressType,
 
     /// Participant Address.
-    pub participant_address: u32,
+    pub participant_address: IcaoAddress,
 }
 
-impl Specifier for Participant {
-    const BITS: usize = 28;
-    type Bytes = u32;
-    type InOut = Participant;
-
-    fn into_bytes(input: Self::InOut) -> Result<Self::Bytes, modular_bitfield::error::OutOfBounds> {
-        unimplemented!()
+impl Participant {
+    /// US civil registration of the participant, if it uses an ICAO address of the US block.
+    ///
+    /// See [`IcaoAddress::n_number`].
+    pub fn n_number(&self) -> Option<String> {
+        match self.address_type {
+            AddressType::ADSBWithICAOAddress | AddressType::TISBWithICAOAddress => {
+                self.participant_address.n_number()
+            }
+            _ => None,
+        }
     }
+}
 
-    fn from_bytes(
-        input: Self::Bytes,
-    ) -> Result<Self::InOut, modular_bitfield::error::InvalidBitPattern<Self::Bytes>> {
-        // input = e.g 76831408 (0x4945AB0) where 
-        // address_type = 0
-        // participant_address = AB4549
-        let address_type = match input & 0x000000F {
+impl From<u8> for AddressType {
+    fn from(value: u8) -> Self {
+        match value {
             0 => AddressType::ADSBWithICAOAddress,
             1 => AddressType::ADSBWithSelfAssignedAddress,
             2 => AddressType::TISBWithICAOAddress,
@@ -204,11 +515,21 @@ impl Specifier for Participant {
             4 => AddressType::SurfaceVehicle,
             5 => AddressType::GroundStationBeacon,
             _ => AddressType::Reserved,
-        };
-        // FIXME: simplify this?
-        let participant_address = (((input & 0xFFFFFF0) >> 4) ).to_be() >> 8;
-        let result = Participant { address_type, participant_address };
-        Ok(result)
+        }
+    }
+}
+
+impl From<AddressType> for u8 {
+    fn from(value: AddressType) -> Self {
+        match value {
+            AddressType::ADSBWithICAOAddress => 0,
+            AddressType::ADSBWithSelfAssignedAddress => 1,
+            AddressType::TISBWithICAOAddress => 2,
+            AddressType::TISBWithTrackFileID => 3,
+            AddressType::SurfaceVehicle => 4,
+            AddressType::GroundStationBeacon => 5,
+            AddressType::Reserved => 6,
+        }
     }
 }
 
@@ -218,26 +539,22 @@ impl Specifier for MiscIndicator {
     type InOut = MiscIndicator;
 
     fn into_bytes(input: Self::InOut) -> Result<Self::Bytes, modular_bitfield::error::OutOfBounds> {
-        unimplemented!()
+        let track_type = TrackType::into_bytes(input.track_type)?;
+        Ok(track_type | (input.extrapolated as u8) << 2 | (input.airborne as u8) << 3)
     }
 
     fn from_bytes(
         input: Self::Bytes,
     ) -> Result<Self::InOut, modular_bitfield::error::InvalidBitPattern<Self::Bytes>> {
-        dbg!(input);
-        // FIXME!
-        let res = match u8::swap_bytes(input & 0b0011) {
-            // check bit 0 and 1
-            0b00 => MiscIndicator::TrackHeadingNotValid,
-            0b01 => MiscIndicator::TrackHeadingTrueTrackAngle,
-            0b10 => MiscIndicator::TrackHeadingMagnetic,
-            0b11 => MiscIndicator::TrackHeadingTrue,
-            _ => {
-                // TODO: handle bit 2 and 3?
-                MiscIndicator::OnGround
-            }
-        };
-        Ok(res)
+        if input > 0x0F {
+            return Err(modular_bitfield::error::InvalidBitPattern::new(input));
+        }
+        Ok(MiscIndicator {
+            track_type: TrackType::from_bytes(input & 0b0011)
+                .map_err(|_| modular_bitfield::error::InvalidBitPattern::new(input))?,
+            extrapolated: input & 0b0100 != 0,
+            airborne: input & 0b1000 != 0,
+        })
     }
 }
 
@@ -250,30 +567,42 @@ pub enum Altitude {
     InvalidOrUnknown,
 }
 
+/// 12-bit altitude, the raw value being in units of 25 feet with a -1000 feet offset.
 impl Specifier for Altitude {
-    const BITS: usize = 16; // FIXME: should be 12 and 4 for misc, but cant get it to work
+    const BITS: usize = 12;
     type Bytes = u16;
     type InOut = Altitude;
 
     fn into_bytes(input: Self::InOut) -> Result<Self::Bytes, modular_bitfield::error::OutOfBounds> {
-        unimplemented!()
+        let raw = match input {
+            Altitude::Valid(value) => {
+                let raw = (value - GDL90_ALTITUDE_OFFSET).div_euclid(GDL90_ALTITUDE_FACTOR);
+                // 0xFFF is reserved for invalid or unknown altitudes
+                if !(0..0xFFF).contains(&raw) {
+                    return Err(modular_bitfield::error::OutOfBounds);
+                }
+                raw as u16
+            }
+            Altitude::InvalidOrUnknown => 0xFFF,
+        };
+        Ok(raw)
     }
 
     fn from_bytes(
         input: Self::Bytes,
     ) -> Result<Self::InOut, modular_bitfield::error::InvalidBitPattern<Self::Bytes>> {
-        let swapped = u16::swap_bytes(input) >> 4;
-        let factored = swapped as i32 * GDL90_ALTITUDE_FACTOR;
-        let value = factored + GDL90_ALTITUDE_OFFSET;
-        if value == 0xFFF {
-            Ok(Altitude::InvalidOrUnknown)
-        } else {
-            Ok(Altitude::Valid(value))
+        if input > 0xFFF {
+            return Err(modular_bitfield::error::InvalidBitPattern::new(input));
+        }
+        if input == 0xFFF {
+            return Ok(Altitude::InvalidOrUnknown);
         }
+        let factored = input as i32 * GDL90_ALTITUDE_FACTOR;
+        Ok(Altitude::Valid(factored + GDL90_ALTITUDE_OFFSET))
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct CallSignType {
     pub tail_number: String,
 }
@@ -284,13 +613,20 @@ impl Specifier for CallSignType {
     type InOut = CallSignType;
 
     fn into_bytes(input: Self::InOut) -> Result<Self::Bytes, modular_bitfield::error::OutOfBounds> {
-        unimplemented!()
+        let bytes = input.tail_number.as_bytes();
+        if bytes.len() > 8 {
+            return Err(modular_bitfield::error::OutOfBounds);
+        }
+        // unused characters are filled with spaces
+        let mut buffer = [b' '; 8];
+        buffer[..bytes.len()].copy_from_slice(bytes);
+        Ok(u64::from_le_bytes(buffer))
     }
 
     fn from_bytes(
         input: Self::Bytes,
     ) -> Result<Self::InOut, modular_bitfield::error::InvalidBitPattern<Self::Bytes>> {
-        let str = std::str::from_utf8(&input.to_le_bytes())
+        let str = core::str::from_utf8(&input.to_le_bytes())
             .unwrap_or("invalid_call_sign")
             .trim()
             .to_string();
@@ -307,7 +643,15 @@ impl Specifier for Cord {
     type InOut = f32;
 
     fn into_bytes(input: Self::InOut) -> Result<Self::Bytes, modular_bitfield::error::OutOfBounds> {
-        unimplemented!()
+        if !(-180.0..=180.0).contains(&input) {
+            return Err(modular_bitfield::error::OutOfBounds);
+        }
+
+        // convert from degrees to semicircle, 180 degrees wraps to -180
+        let value = round(input * ((1 << 23) as f32 / 180.0)) as i32;
+        let combined = (value as u32) & 0x00FFFFFF;
+
+        Ok(u32::swap_bytes(combined << 8))
     }
 
     fn from_bytes(
@@ -352,7 +696,31 @@ impl Specifier for Velocity {
     type InOut = Velocity;
 
     fn into_bytes(input: Self::InOut) -> Result<Self::Bytes, modular_bitfield::error::OutOfBounds> {
-        unimplemented!()
+        // horizontal
+        let combined_h = match input.h_vel {
+            VelocityType::Horizontal(x) if x / GDL90_HORZ_VELOCITY_FACTOR < 0xFFF => {
+                (x / GDL90_HORZ_VELOCITY_FACTOR) as u32
+            }
+            VelocityType::Unavailable => 0xFFF,
+            _ => return Err(modular_bitfield::error::OutOfBounds),
+        };
+
+        // vertical
+        let combined_v = match input.v_vel {
+            VelocityType::Vertical(x) => {
+                let value = x / GDL90_VERT_VELOCITY_FACTOR;
+                if !(-0x1FE..=0x1FE).contains(&value) {
+                    return Err(modular_bitfield::error::OutOfBounds);
+                }
+                // 2s complement for negative values
+                (value as u32) & 0xFFF
+            }
+            VelocityType::Unavailable => 0x800,
+            _ => return Err(modular_bitfield::error::OutOfBounds),
+        };
+
+        let input = (combined_h << 12) | combined_v;
+        Ok(u32::swap_bytes(input << 8))
     }
 
     fn from_bytes(
@@ -377,9 +745,7 @@ impl Specifier for Velocity {
         let v_vel = if combined_v == 0x800 {
             // no vertical velocity info available
             VelocityType::Unavailable
-        } else if (combined_v >= 0x1FF && combined_v <= 0x7FF)
-            || (combined_v >= 0x801 && combined_v <= 0xE01)
-        {
+        } else if (0x1FF..=0x7FF).contains(&combined_v) || (0x801..=0xE01).contains(&combined_v) {
             // not used, invalid ranges
             VelocityType::Unavailable
         } else if combined_v > 2047 {
@@ -395,7 +761,9 @@ impl Specifier for Velocity {
 
 #[cfg(test)]
 mod tests {
-    use std::io::Cursor;
+    use alloc::borrow::ToOwned;
+    use alloc::vec::Vec;
+    use binrw::io::Cursor;
 
     use super::*;
 
@@ -416,6 +784,107 @@ mod tests {
         assert_eq!(Cord::from_bytes(0x000080).unwrap(), -180.0);
     }
 
+    #[test]
+    fn callsign_into_bytes_works() {
+        let call_sign = CallSignType {
+            tail_number: "N825V".to_owned(),
+        };
+        assert_eq!(
+            CallSignType::into_bytes(call_sign).unwrap(),
+            u64::to_be(0x4e38323556202020)
+        );
+    }
+
+    #[test]
+    fn latitude_into_bytes_works() {
+        assert_eq!(Cord::into_bytes(0.0).unwrap(), 0x000000);
+        assert_eq!(Cord::into_bytes(45.0).unwrap(), 0x000020);
+        assert_eq!(Cord::into_bytes(-45.0).unwrap(), 0x0000E0);
+        assert_eq!(Cord::into_bytes(90.0).unwrap(), 0x000040);
+        assert_eq!(Cord::into_bytes(-180.0).unwrap(), 0x000080);
+        assert!(Cord::into_bytes(180.5).is_err());
+    }
+
+    #[test]
+    fn altitude_into_bytes_works() {
+        for altitude in [
+            Altitude::Valid(-1000),
+            Altitude::Valid(2500),
+            Altitude::InvalidOrUnknown,
+        ] {
+            let bytes = Altitude::into_bytes(altitude).unwrap();
+            let parsed = Altitude::from_bytes(bytes).unwrap();
+            assert_eq!(Altitude::into_bytes(parsed).unwrap(), bytes);
+        }
+        assert_eq!(
+            Altitude::from_bytes(Altitude::into_bytes(Altitude::Valid(2500)).unwrap()).unwrap(),
+            Altitude::Valid(2500)
+        );
+        assert_eq!(Altitude::into_bytes(Altitude::Valid(5000)).unwrap(), 0x0F0);
+        // below -1000 feet
+        assert!(Altitude::into_bytes(Altitude::Valid(-1010)).is_err());
+        assert!(Altitude::into_bytes(Altitude::Valid(-1025)).is_err());
+        assert_eq!(
+            Altitude::from_bytes(0xFFF).unwrap(),
+            Altitude::InvalidOrUnknown
+        );
+        assert!(Altitude::from_bytes(0x1000).is_err());
+    }
+
+    #[test]
+    fn participant_works() {
+        let participant = Participant {
+            address_type: AddressType::TISBWithTrackFileID,
+            participant_address: IcaoAddress::new(0xAB4549).unwrap(),
+        };
+        let report = Report::new()
+            .with_traffic_alert_status(TrafficAlert::TrafficAlert)
+            .with_participant(participant)
+            .with_emergency_priority_code(EmergencyPriorityCodeCategory::MinimumFuel);
+        assert_eq!(report.participant(), participant);
+        assert_eq!(report.traffic_alert_status(), TrafficAlert::TrafficAlert);
+
+        // st aa aa aa ... px
+        let bytes = report.into_bytes();
+        assert_eq!(bytes[..4], [0x13, 0xAB, 0x45, 0x49]);
+        assert_eq!(bytes[26], 0x30);
+
+        let report = Report::from_bytes(bytes);
+        assert_eq!(report.participant(), participant);
+        assert_eq!(
+            report.emergency_priority_code(),
+            EmergencyPriorityCodeCategory::MinimumFuel
+        );
+        assert_eq!(report.reserved(), 0);
+
+        // track file IDs are not registrations
+        assert_eq!(participant.n_number(), None);
+        let participant = Participant {
+            address_type: AddressType::ADSBWithICAOAddress,
+            ..participant
+        };
+        assert_eq!(participant.n_number().as_deref(), Some("N825V"));
+    }
+
+    #[test]
+    fn velocity_into_bytes_works() {
+        let velocity = Velocity {
+            h_vel: VelocityType::Horizontal(123),
+            v_vel: VelocityType::Vertical(64),
+        };
+        assert_eq!(Velocity::into_bytes(velocity).unwrap(), 0x01b_007);
+        let velocity = Velocity {
+            h_vel: VelocityType::Horizontal(0),
+            v_vel: VelocityType::Vertical(-64),
+        };
+        assert_eq!(Velocity::into_bytes(velocity).unwrap(), 0xFF_0F_00);
+        let velocity = Velocity {
+            h_vel: VelocityType::Horizontal(0),
+            v_vel: VelocityType::Unavailable,
+        };
+        assert_eq!(Velocity::into_bytes(velocity).unwrap(), 0x00_08_00);
+    }
+
     #[test]
     fn vertical() {
         // 01 b0 07 -> will get reversed to 07 b0 01 -> horizontal = 07b, vertical = 001
@@ -519,9 +988,107 @@ mod tests {
             0x20, // cc
             0x00, // px
         ];
-        let report = Report::read(&mut Cursor::new(data)).unwrap();
+        let report = Report::read(&mut Cursor::new(&data)).unwrap();
         assert_eq!(report.participant().address_type, AddressType::ADSBWithICAOAddress);
-        assert_eq!(report.participant().participant_address.to_string(), "11224393"); // AB4549
-        dbg!(&report);
+        assert_eq!(report.participant().participant_address.to_string(), "AB4549");
+        assert_eq!(report.altitude(), Altitude::Valid(5000));
+        assert_eq!(report.nic(), Nic::Rc25M);
+        assert_eq!(report.track_heading().angle(), Some(45.0));
+        assert_eq!(report.track_heading().kind, TrackType::TrueTrackAngle);
+        assert_eq!(report.nacp(), Nacp::Epu30M);
+        assert_eq!(
+            report.misc_indicators(),
+            MiscIndicator {
+                track_type: TrackType::TrueTrackAngle,
+                extrapolated: false,
+                airborne: true,
+            }
+        );
+
+        let mut output = Cursor::new(vec![]);
+        report.write(&mut output).unwrap();
+        assert_eq!(output.into_inner(), data);
+    }
+
+    #[test]
+    fn accuracy_categories_work() {
+        assert_eq!(Nacp::Unknown.epu_meters(), None);
+        assert_eq!(Nacp::Epu1Nm.epu_meters(), Some(1852.0));
+        assert_eq!(Nacp::Epu10M.epu_meters(), Some(10.0));
+        assert_eq!(Nacp::Epu10M.vertical_epu_meters(), Some(15.0));
+        assert_eq!(Nacp::Epu0p5Nm.vertical_epu_meters(), None);
+        assert_eq!(Nacp::Reserved0.epu_meters(), None);
+        assert_eq!(Nacp::from_bytes(11).unwrap(), Nacp::Epu3M);
+
+        assert_eq!(Nic::Unknown.containment_radius_meters(), None);
+        assert_eq!(Nic::Rc20Nm.containment_radius_meters(), Some(37040.0));
+        assert_eq!(Nic::Rc7p5M.containment_radius_meters(), Some(7.5));
+        assert_eq!(Nic::Rc25M.vertical_protection_limit_meters(), Some(37.5));
+        assert_eq!(Nic::from_bytes(8).unwrap(), Nic::Rc0p1Nm);
+        assert_eq!(Nic::into_bytes(Nic::Rc75M).unwrap(), 9);
+    }
+
+    #[test]
+    fn track_heading_works() {
+        let track = TrackHeading::from_raw(0x80, TrackType::MagneticHeading);
+        assert_eq!(track.angle(), Some(180.0));
+        assert_eq!(track.to_raw(), 0x80);
+        assert_eq!(
+            TrackHeading::from_raw(0x80, TrackType::NotValid).angle(),
+            None
+        );
+
+        assert_eq!(TrackHeading::new(TrackType::TrueHeading, 359.9).to_raw(), 0);
+        assert_eq!(
+            TrackHeading::new(TrackType::TrueHeading, -90.0).to_raw(),
+            0xC0
+        );
+        assert_eq!(TrackHeading::new(TrackType::TrueHeading, 1.4).to_raw(), 1);
+
+        // the type is kept in the misc indicators, without changing the other bits
+        let report = Report::new()
+            .with_misc_indicators(MiscIndicator {
+                track_type: TrackType::NotValid,
+                extrapolated: false,
+                airborne: true,
+            })
+            .with_track_heading(TrackHeading::new(TrackType::TrueHeading, 90.0));
+        assert_eq!(
+            report.track_heading(),
+            TrackHeading::new(TrackType::TrueHeading, 90.0)
+        );
+        assert!(report.misc_indicators().airborne);
+        let bytes = report.into_bytes();
+        assert_eq!(bytes[10..12], [0x00, 0x0B]);
+        assert_eq!(bytes[16], 0x40);
+    }
+
+    #[test]
+    fn misc_indicators_work() {
+        for raw in 0..=0x0F {
+            let misc = MiscIndicator::from_bytes(raw).unwrap();
+            assert_eq!(MiscIndicator::into_bytes(misc).unwrap(), raw);
+        }
+        assert!(MiscIndicator::from_bytes(0x10).is_err());
+
+        // setting one of them keeps the other
+        let misc = MiscIndicator {
+            track_type: TrackType::MagneticHeading,
+            extrapolated: true,
+            airborne: false,
+        };
+        let mut report = Report::new()
+            .with_altitude(Altitude::Valid(-1000))
+            .with_misc_indicators(misc);
+        assert_eq!(report.altitude(), Altitude::Valid(-1000));
+        assert_eq!(report.misc_indicators(), misc);
+
+        report.set_altitude(Altitude::InvalidOrUnknown);
+        assert_eq!(report.misc_indicators(), misc);
+        assert_eq!(report.altitude(), Altitude::InvalidOrUnknown);
+        assert!(report
+            .set_altitude_checked(Altitude::Valid(200000))
+            .is_err());
+        assert_eq!(report.into_bytes()[10..12], [0xFF, 0xF6]);
     }
 }
@@ -0,0 +1,440 @@
+//! Stratux vendor messages, only available with the `stratux` feature.
+//!
+//! | ID   | Name              | Type                  |
+//! |------|-------------------|-----------------------|
+//! | 0x4C | AHRS              | [`StratuxAhrs`]       |
+//! | 0x53 | Status            | [`StratuxStatus`]     |
+//! | 0xCC | Heartbeat         | [`StratuxHeartbeat`]  |
+//!
+//! Multi-byte fields are transmitted most significant byte first. Layouts follow the Stratux
+//! encoder (`main/gen_gdl90.go`).
+//!
+//! ## Example
+//! ```
+//! use gdl90::datalink::Gdl90DatalinkMessage;
+//!
+//! let frame = gdl90::frame(0xCC, &[0x07]);
+//! let message = gdl90::read_raw(&frame).unwrap();
+//! if let Gdl90DatalinkMessage::StratuxHeartbeat { heartbeat } = message.message_data {
+//!     assert!(heartbeat.gps_valid());
+//!     assert!(heartbeat.ahrs_valid());
+//!     assert_eq!(heartbeat.protocol_version(), 1);
+//! }
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use binrw::{binrw, BinRead, BinWrite};
+use modular_bitfield::{bitfield, prelude::B6};
+
+use super::round;
+
+/// Value of the signed AHRS fields meaning that they are not available.
+const AHRS_I16_INVALID: i16 = 0x7FFF;
+
+/// Value of the pressure altitude meaning that it is not available.
+const PRESSURE_ALTITUDE_INVALID: u16 = 0xFFFF;
+
+/// Offset added to the pressure altitude, in feet.
+const PRESSURE_ALTITUDE_OFFSET: i32 = 5000;
+
+/// Stratux AHRS message (0x4C), also known as the Levil AHRS message.
+///
+/// | Byte # | Name               | Size  | Value                                             |
+/// |--------|--------------------|-------|---------------------------------------------------|
+/// | 1-3    | Sub-ID and version | 3     | 0x45, 0x01, 0x01                                  |
+/// | 4-5    | Roll               | 2     | Resolution 0.1 deg, 0x7FFF if invalid             |
+/// | 6-7    | Pitch              | 2     | Resolution 0.1 deg, 0x7FFF if invalid             |
+/// | 8-9    | Heading            | 2     | Resolution 0.1 deg, 0x7FFF if invalid             |
+/// | 10-11  | Slip/Skid          | 2     | Resolution 0.1 deg, 0x7FFF if invalid             |
+/// | 12-13  | Turn Rate          | 2     | Resolution 0.1 deg/s, 0x7FFF if invalid           |
+/// | 14-15  | G-Load             | 2     | Resolution 0.1 G, 0x7FFF if invalid               |
+/// | 16-17  | Indicated Airspeed | 2     | Knots, 0x7FFF if invalid                          |
+/// | 18-19  | Pressure Altitude  | 2     | Feet + 5000, 0xFFFF if invalid                    |
+/// | 20-21  | Vertical Speed     | 2     | Feet per minute, 0x7FFF if invalid                |
+/// | 22-23  | Reserved           | 2     | 0x7FFF                                            |
+#[binrw]
+#[brw(big, magic = b"\x45\x01\x01")]
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct StratuxAhrs {
+    /// Roll in degrees, positive being right wing down.
+    #[br(map = decode_tenths)]
+    #[bw(map = |x: &Option<f32>| encode_tenths(*x))]
+    pub roll: Option<f32>,
+
+    /// Pitch in degrees, positive being nose up.
+    #[br(map = decode_tenths)]
+    #[bw(map = |x: &Option<f32>| encode_tenths(*x))]
+    pub pitch: Option<f32>,
+
+    /// Heading in degrees.
+    #[br(map = decode_tenths)]
+    #[bw(map = |x: &Option<f32>| encode_tenths(*x))]
+    pub heading: Option<f32>,
+
+    /// Slip/skid in degrees.
+    #[br(map = decode_tenths)]
+    #[bw(map = |x: &Option<f32>| encode_tenths(*x))]
+    pub slip_skid: Option<f32>,
+
+    /// Turn rate in degrees per second.
+    #[br(map = decode_tenths)]
+    #[bw(map = |x: &Option<f32>| encode_tenths(*x))]
+    pub turn_rate: Option<f32>,
+
+    /// G-load in G.
+    #[br(map = decode_tenths)]
+    #[bw(map = |x: &Option<f32>| encode_tenths(*x))]
+    pub g_load: Option<f32>,
+
+    /// Indicated airspeed in knots.
+    #[br(map = decode_i16)]
+    #[bw(map = |x: &Option<i16>| encode_i16(*x))]
+    pub indicated_airspeed: Option<i16>,
+
+    /// Pressure altitude in feet, from -5000 to 60534.
+    #[br(map = |x: u16| (x != PRESSURE_ALTITUDE_INVALID).then(|| x as i32 - PRESSURE_ALTITUDE_OFFSET))]
+    #[bw(map = |x: &Option<i32>| encode_pressure_altitude(*x))]
+    pub pressure_altitude: Option<i32>,
+
+    /// Vertical speed in feet per minute.
+    #[br(map = decode_i16)]
+    #[bw(map = |x: &Option<i16>| encode_i16(*x))]
+    pub vertical_speed: Option<i16>,
+
+    #[br(temp)]
+    #[bw(calc = AHRS_I16_INVALID)]
+    reserved: i16,
+}
+
+/// Decodes a value in tenths.
+fn decode_tenths(raw: i16) -> Option<f32> {
+    decode_i16(raw).map(|x| x as f32 / 10.0)
+}
+
+/// Encodes a value in tenths, saturated to the valid range.
+fn encode_tenths(value: Option<f32>) -> i16 {
+    encode_i16(value.map(|x| round(x * 10.0).clamp(-32767.0, 32766.0) as i16))
+}
+
+/// Decodes a signed value.
+fn decode_i16(raw: i16) -> Option<i16> {
+    (raw != AHRS_I16_INVALID).then_some(raw)
+}
+
+/// Encodes a signed value, saturated to the valid range.
+fn encode_i16(value: Option<i16>) -> i16 {
+    value.map_or(AHRS_I16_INVALID, |x| x.min(AHRS_I16_INVALID - 1))
+}
+
+/// Encodes the pressure altitude, saturated to the valid range.
+fn encode_pressure_altitude(value: Option<i32>) -> u16 {
+    value.map_or(PRESSURE_ALTITUDE_INVALID, |x| {
+        (x + PRESSURE_ALTITUDE_OFFSET).clamp(0, PRESSURE_ALTITUDE_INVALID as i32 - 1) as u16
+    })
+}
+
+pub use self::bitfields::StratuxHeartbeat;
+
+// Lints triggered by the `modular_bitfield` expansion.
+#[allow(unused_parens, clippy::identity_op, clippy::new_without_default)]
+mod bitfields {
+    use super::*;
+
+    /// Stratux Heartbeat message (0xCC), see `makeStratuxHeartbeat`.
+    ///
+    /// | Bit | Description        |
+    /// |-----|--------------------|
+    /// | 7-2 | Protocol version   |
+    /// | 1   | GPS valid          |
+    /// | 0   | AHRS valid         |
+    #[bitfield]
+    #[derive(BinRead, BinWrite, Debug, PartialEq, Clone, Copy)]
+    #[brw(little)]
+    pub struct StratuxHeartbeat {
+        pub ahrs_valid: bool,
+        pub gps_valid: bool,
+        pub protocol_version: B6,
+    }
+}
+
+/// Stratux Status message (0x53, `S`), see `makeStratuxStatus`. Only version 1 is supported.
+///
+/// | Byte # | Name                      | Size  | Value                                 |
+/// |--------|---------------------------|-------|---------------------------------------|
+/// | 1      | Status version            | 1     | 1                                     |
+/// | 2-5    | Software version          | 4     | see [`StratuxStatus::version`]        |
+/// | 6-9    | Hardware revision         | 4     |                                       |
+/// | 10-13  | Valid and enabled flags   | 4     | see [`StratuxStatus::flags`]          |
+/// | 14-15  | Connected hardware        | 2     | Bits 1-0: number of radios            |
+/// | 16     | GPS satellites locked     | 1     |                                       |
+/// | 17     | GPS satellites tracked    | 1     |                                       |
+/// | 18-19  | UAT traffic targets       | 2     |                                       |
+/// | 20-21  | 1090ES traffic targets    | 2     |                                       |
+/// | 22-23  | UAT messages per minute   | 2     |                                       |
+/// | 24-25  | 1090ES messages per minute| 2     |                                       |
+/// | 26-27  | CPU temperature           | 2     | Resolution 0.1 °C                     |
+/// | 28     | ADS-B towers count        | 1     |                                       |
+/// | 29-N   | ADS-B towers              | 6 * count | see [`StratuxTower`]              |
+#[binrw]
+#[brw(big, magic = b"\x01")]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct StratuxStatus {
+    /// Software version: major, minor, release type (eg. `b'r'`) and build, eg. `v1.6r1`.
+    pub version: [u8; 4],
+
+    pub hardware_revision: u32,
+
+    /// Valid and enabled flags.
+    ///
+    /// | Bits | Description                                  |
+    /// |------|----------------------------------------------|
+    /// | 1-0  | GPS fix: 0 none, 1 3D, 2 DGPS (SBAS/WAAS)     |
+    /// | 2    | AHRS valid                                   |
+    /// | 3    | Pressure altitude valid                      |
+    /// | 4    | CPU temperature valid                        |
+    /// | 5    | UAT enabled                                  |
+    /// | 6    | 1090ES enabled                               |
+    /// | 7    | GPS enabled                                  |
+    pub flags: u32,
+
+    pub connected_hardware: u16,
+
+    pub gps_satellites_locked: u8,
+
+    pub gps_satellites_tracked: u8,
+
+    pub uat_traffic_targets: u16,
+
+    pub es_traffic_targets: u16,
+
+    pub uat_messages_per_minute: u16,
+
+    pub es_messages_per_minute: u16,
+
+    /// CPU temperature in 0.1 °C, see [`StratuxStatus::cpu_temperature`].
+    pub cpu_temperature_raw: i16,
+
+    #[br(temp)]
+    #[bw(calc = towers.len() as u8)]
+    tower_count: u8,
+
+    /// ADS-B ground stations being received.
+    #[br(count = tower_count)]
+    pub towers: Vec<StratuxTower>,
+}
+
+/// GPS fix quality, see [`StratuxStatus::gps_fix`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum StratuxGpsFix {
+    None,
+    Fix3d,
+    Dgps,
+}
+
+impl StratuxStatus {
+    /// Software version, eg. `v1.6r1`.
+    pub fn version(&self) -> String {
+        let [major, minor, kind, build] = self.version;
+        format!("v{major}.{minor}{}{build}", char::from(kind))
+    }
+
+    /// GPS fix quality.
+    pub fn gps_fix(&self) -> StratuxGpsFix {
+        match self.flags & 0x03 {
+            1 => StratuxGpsFix::Fix3d,
+            2 => StratuxGpsFix::Dgps,
+            _ => StratuxGpsFix::None,
+        }
+    }
+
+    /// Whether the AHRS data is valid.
+    pub fn ahrs_valid(&self) -> bool {
+        self.flags & (1 << 2) != 0
+    }
+
+    /// Whether the pressure altitude is valid.
+    pub fn pressure_altitude_valid(&self) -> bool {
+        self.flags & (1 << 3) != 0
+    }
+
+    /// CPU temperature in °C, `None` if it is not valid.
+    pub fn cpu_temperature(&self) -> Option<f32> {
+        (self.flags & (1 << 4) != 0).then(|| self.cpu_temperature_raw as f32 / 10.0)
+    }
+
+    /// Whether the UAT receiver is enabled.
+    pub fn uat_enabled(&self) -> bool {
+        self.flags & (1 << 5) != 0
+    }
+
+    /// Whether the 1090ES receiver is enabled.
+    pub fn es_enabled(&self) -> bool {
+        self.flags & (1 << 6) != 0
+    }
+
+    /// Whether the GPS is enabled.
+    pub fn gps_enabled(&self) -> bool {
+        self.flags & (1 << 7) != 0
+    }
+
+    /// Number of connected radios.
+    pub fn radios(&self) -> u8 {
+        (self.connected_hardware & 0x03) as u8
+    }
+}
+
+/// ADS-B ground station position, see [`StratuxStatus::towers`].
+#[binrw]
+#[brw(big)]
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct StratuxTower {
+    /// Latitude in degrees.
+    #[br(parse_with = binrw::helpers::read_u24, map = semicircles_to_degrees)]
+    #[bw(write_with = write_semicircles)]
+    pub latitude: f32,
+
+    /// Longitude in degrees.
+    #[br(parse_with = binrw::helpers::read_u24, map = semicircles_to_degrees)]
+    #[bw(write_with = write_semicircles)]
+    pub longitude: f32,
+}
+
+/// Converts a 24-bit signed semicircle value to degrees.
+fn semicircles_to_degrees(raw: u32) -> f32 {
+    // sign extension of the 24-bit value
+    let value = ((raw << 8) as i32) >> 8;
+    value as f32 * (180.0 / (1 << 23) as f32)
+}
+
+/// Writes degrees as a 24-bit signed semicircle value.
+#[binrw::writer(writer, endian)]
+fn write_semicircles(degrees: &f32) -> binrw::BinResult<()> {
+    binrw::helpers::write_u24(&degrees_to_semicircles(*degrees), writer, endian, ())
+}
+
+/// Converts degrees to a 24-bit signed semicircle value.
+fn degrees_to_semicircles(degrees: f32) -> u32 {
+    let value = round(degrees * ((1 << 23) as f32 / 180.0)) as i32;
+    value as u32 & 0xFFFFFF
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use binrw::io::Cursor;
+
+    fn write<T>(value: &T) -> Vec<u8>
+    where
+        T: BinWrite + binrw::meta::WriteEndian,
+        for<'a> T::Args<'a>: Default,
+    {
+        let mut output = Cursor::new(vec![]);
+        value.write(&mut output).unwrap();
+        output.into_inner()
+    }
+
+    #[test]
+    fn ahrs_works() {
+        let data = [
+            0x45, 0x01, 0x01, // sub-ID and version
+            0xFF, 0x9C, // roll -10.0
+            0x00, 0x32, // pitch 5.0
+            0x0E, 0x10, // heading 360.0
+            0x7F, 0xFF, // slip/skid invalid
+            0xFF, 0xE2, // turn rate -3.0
+            0x00, 0x0A, // g-load 1.0
+            0x00, 0x78, // ias 120
+            0x1B, 0x58, // pressure altitude 2000
+            0xFE, 0x0C, // vertical speed -500
+            0x7F, 0xFF, // reserved
+        ];
+        let ahrs = StratuxAhrs::read(&mut Cursor::new(&data)).unwrap();
+        assert_eq!(ahrs.roll, Some(-10.0));
+        assert_eq!(ahrs.pitch, Some(5.0));
+        assert_eq!(ahrs.heading, Some(360.0));
+        assert_eq!(ahrs.slip_skid, None);
+        assert_eq!(ahrs.turn_rate, Some(-3.0));
+        assert_eq!(ahrs.g_load, Some(1.0));
+        assert_eq!(ahrs.indicated_airspeed, Some(120));
+        assert_eq!(ahrs.pressure_altitude, Some(2000));
+        assert_eq!(ahrs.vertical_speed, Some(-500));
+        assert_eq!(write(&ahrs), data);
+
+        let data = write(&StratuxAhrs::default());
+        assert_eq!(
+            data[3..],
+            [
+                0x7F, 0xFF, 0x7F, 0xFF, 0x7F, 0xFF, 0x7F, 0xFF, 0x7F, 0xFF, 0x7F, 0xFF, 0x7F, 0xFF,
+                0xFF, 0xFF, 0x7F, 0xFF, 0x7F, 0xFF
+            ]
+        );
+    }
+
+    #[test]
+    fn heartbeat_works() {
+        // GPS valid is 0x02, AHRS valid is 0x01
+        let heartbeat = StratuxHeartbeat::read(&mut Cursor::new([0x06])).unwrap();
+        assert!(heartbeat.gps_valid());
+        assert!(!heartbeat.ahrs_valid());
+        assert_eq!(heartbeat.protocol_version(), 1);
+
+        let heartbeat = StratuxHeartbeat::read(&mut Cursor::new([0x05])).unwrap();
+        assert!(!heartbeat.gps_valid());
+        assert!(heartbeat.ahrs_valid());
+    }
+
+    #[test]
+    fn status_works() {
+        let mut data = vec![
+            0x01, // status version
+            1, 6, b'r', 1, // software version
+            0xFF, 0xFF, 0xFF, 0xFF, // hardware revision
+            0x00, 0x00, 0x00, 0x3D, // flags
+            0x00, 0x02, // connected hardware
+            8, 12, // satellites
+            0x00, 0x05, 0x00, 0x0A, // traffic targets
+            0x01, 0x00, 0x02, 0x00, // messages per minute
+            0x01, 0xC2, // cpu temperature
+            0x01, // towers
+        ];
+        data.extend_from_slice(&[0x20, 0x00, 0x00, 0xC0, 0x00, 0x00]);
+
+        let status = StratuxStatus::read(&mut Cursor::new(&data)).unwrap();
+        assert_eq!(status.version(), "v1.6r1");
+        // 0x3D: 3D fix, AHRS, pressure altitude and CPU temperature valid, UAT enabled
+        assert_eq!(status.gps_fix(), StratuxGpsFix::Fix3d);
+        assert!(status.ahrs_valid());
+        assert!(status.pressure_altitude_valid());
+        assert_eq!(status.cpu_temperature(), Some(45.0));
+        assert!(status.uat_enabled());
+        assert!(!status.es_enabled());
+        assert!(!status.gps_enabled());
+        assert_eq!(status.radios(), 2);
+        assert_eq!(status.gps_satellites_locked, 8);
+        assert_eq!(status.gps_satellites_tracked, 12);
+        assert_eq!(status.uat_traffic_targets, 5);
+        assert_eq!(status.es_traffic_targets, 10);
+        assert_eq!(status.uat_messages_per_minute, 256);
+        assert_eq!(status.es_messages_per_minute, 512);
+        assert_eq!(status.towers.len(), 1);
+        assert_eq!(status.towers[0].latitude, 45.0);
+        assert_eq!(status.towers[0].longitude, -90.0);
+        assert_eq!(write(&status), data);
+
+        // 0xC2: DGPS fix, 1090ES and GPS enabled
+        let status = StratuxStatus {
+            flags: 0xC2,
+            ..status
+        };
+        assert_eq!(status.gps_fix(), StratuxGpsFix::Dgps);
+        assert!(!status.ahrs_valid());
+        assert!(!status.pressure_altitude_valid());
+        assert_eq!(status.cpu_temperature(), None);
+        assert!(!status.uat_enabled());
+        assert!(status.es_enabled());
+        assert!(status.gps_enabled());
+    }
+}
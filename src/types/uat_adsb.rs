@@ -0,0 +1,781 @@
+//! UAT ADS-B message payload, passed through by Basic and Long Reports. 560-1058-00 Rev A - ref 3.6.
+//!
+//! The payload is decoded as specified by RTCA DO-282B - ref 2.2.4.5.
+//!
+//! | Payload Type | Elements (Byte #)                       | Length    |
+//! |--------------|-----------------------------------------|-----------|
+//! | 0            | HDR (1-4), SV (5-17)                    | 18 (Basic)|
+//! | 1            | HDR, SV, MS (18-29), AUX SV (30-34)     | 34 (Long) |
+//! | 2            | HDR, SV, AUX SV (30-34)                 | 34 (Long) |
+//! | 3            | HDR, SV, MS (18-29), TS (30-34)         | 34 (Long) |
+//! | 4            | HDR, SV, TS (30-34)                     | 34 (Long) |
+//! | 5            | HDR, SV, TS (25-29), AUX SV (30-34)     | 34 (Long) |
+//! | 6            | HDR, SV, TS (25-29), AUX SV (30-34)     | 34 (Long) |
+//! | 7-10         | HDR, SV                                 | 34 (Long) |
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use binrw::{binrw, BinRead, BinWrite};
+use modular_bitfield::Specifier;
+
+use super::icao::IcaoAddress;
+use super::read_bits;
+use super::report::{Altitude, EmergencyPriorityCodeCategory, EmmiterCategory, Nacp, Nic};
+
+/// Length of the UAT ADS-B payload of a Basic Report.
+pub const UAT_BASIC_PAYLOAD_LEN: usize = 18;
+
+/// Length of the UAT ADS-B payload of a Long Report.
+pub const UAT_LONG_PAYLOAD_LEN: usize = 34;
+
+const UAT_ALTITUDE_FACTOR: i32 = 25;
+const UAT_ALTITUDE_OFFSET: i32 = -1000;
+const UAT_VERT_VELOCITY_FACTOR: i32 = 64;
+
+/// UAT ADS-B payload (18 bytes for Basic Reports and 34 bytes for Long Reports).
+///
+/// The Header and State Vector are always present, the remaining elements depend
+/// on [`UatHeader::payload_type_code`].
+#[binrw]
+#[brw(big)]
+#[br(import(len: usize))]
+#[derive(Debug, PartialEq)]
+pub struct UatAdsbPayload {
+    /// Header element (HDR).
+    pub header: UatHeader,
+
+    /// State Vector element (SV).
+    pub state_vector: StateVector,
+
+    /// Remaining payload bytes, see [`UatAdsbPayload::mode_status`],
+    /// [`UatAdsbPayload::aux_state_vector`] and [`UatAdsbPayload::target_state`].
+    #[br(count = len.saturating_sub(17))]
+    pub elements: Vec<u8>,
+}
+
+impl UatAdsbPayload {
+    /// Mode Status element (MS), present in payload types 1 and 3.
+    pub fn mode_status(&self) -> Option<ModeStatus> {
+        match self.header.payload_type_code() {
+            1 | 3 => self.element(0).map(ModeStatus),
+            _ => None,
+        }
+    }
+
+    /// Auxiliary State Vector element (AUX SV), present in payload types 1, 2, 5 and 6.
+    pub fn aux_state_vector(&self) -> Option<AuxStateVector> {
+        match self.header.payload_type_code() {
+            1 | 2 | 5 | 6 => self.element(12).map(AuxStateVector),
+            _ => None,
+        }
+    }
+
+    /// Target State element (TS), present in payload types 3, 4, 5 and 6.
+    pub fn target_state(&self) -> Option<TargetState> {
+        match self.header.payload_type_code() {
+            3 | 4 => self.element(12).map(TargetState),
+            5 | 6 => self.element(7).map(TargetState),
+            _ => None,
+        }
+    }
+
+    /// Copies the element starting at `offset` of [`UatAdsbPayload::elements`].
+    fn element<const N: usize>(&self, offset: usize) -> Option<[u8; N]> {
+        self.elements.get(offset..offset + N)?.try_into().ok()
+    }
+}
+
+/// Header element (HDR). DO-282B - ref 2.2.4.5.1.
+///
+/// | Bits  | Name                  |
+/// |-------|-----------------------|
+/// | 0-4   | Payload Type Code     |
+/// | 5-7   | Address Qualifier     |
+/// | 8-31  | Address               |
+#[derive(BinRead, BinWrite, Debug, PartialEq, Clone, Copy)]
+pub struct UatHeader(pub [u8; 4]);
+
+impl UatHeader {
+    /// Payload type code, which defines the elements of the payload.
+    pub fn payload_type_code(&self) -> u8 {
+        read_bits(&self.0, 0, 5) as u8
+    }
+
+    /// Type of [`UatHeader::address`].
+    pub fn address_qualifier(&self) -> AddressQualifier {
+        match read_bits(&self.0, 5, 3) {
+            0 => AddressQualifier::AdsbWithIcaoAddress,
+            1 => AddressQualifier::AdsbWithSelfAssignedAddress,
+            2 => AddressQualifier::TisbWithIcaoAddress,
+            3 => AddressQualifier::TisbWithTrackFileId,
+            4 => AddressQualifier::SurfaceVehicle,
+            5 => AddressQualifier::FixedAdsbBeacon,
+            6 => AddressQualifier::AdsrWithNonIcaoAddress,
+            _ => AddressQualifier::Reserved,
+        }
+    }
+
+    /// 24-bit participant address.
+    pub fn address(&self) -> IcaoAddress {
+        IcaoAddress::from_be_bytes([self.0[1], self.0[2], self.0[3]])
+    }
+}
+
+/// 3-bit field which describes the type of address conveyed in the [`UatHeader::address`] field.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AddressQualifier {
+    AdsbWithIcaoAddress,
+    AdsbWithSelfAssignedAddress,
+    TisbWithIcaoAddress,
+    TisbWithTrackFileId,
+    SurfaceVehicle,
+    FixedAdsbBeacon,
+    AdsrWithNonIcaoAddress,
+    Reserved,
+}
+
+/// State Vector element (SV). DO-282B - ref 2.2.4.5.2.
+///
+/// | Bits    | Name                                                      |
+/// |---------|-----------------------------------------------------------|
+/// | 0-22    | Latitude                                                  |
+/// | 23-46   | Longitude                                                 |
+/// | 47      | Altitude Type                                             |
+/// | 48-59   | Altitude                                                  |
+/// | 60-63   | NIC                                                       |
+/// | 64-65   | Air/Ground State                                          |
+/// | 66      | Reserved                                                  |
+/// | 67-77   | North/South Velocity or Ground Speed                      |
+/// | 78-88   | East/West Velocity or Track/Heading                       |
+/// | 89-99   | Vertical Velocity or A/V Length and Width                 |
+/// | 100-103 | UTC coupled and Uplink Feedback, or TIS-B Site ID         |
+#[derive(BinRead, BinWrite, Debug, PartialEq, Clone, Copy)]
+pub struct StateVector(pub [u8; 13]);
+
+impl StateVector {
+    /// Latitude in degrees.
+    pub fn latitude(&self) -> f32 {
+        let raw = read_bits(&self.0, 0, 23);
+        let degrees = raw as f32 * (360.0 / (1 << 24) as f32);
+        if degrees > 90.0 {
+            degrees - 180.0
+        } else {
+            degrees
+        }
+    }
+
+    /// Longitude in degrees.
+    pub fn longitude(&self) -> f32 {
+        let raw = read_bits(&self.0, 23, 24);
+        let degrees = raw as f32 * (360.0 / (1 << 24) as f32);
+        if degrees > 180.0 {
+            degrees - 360.0
+        } else {
+            degrees
+        }
+    }
+
+    /// Whether latitude and longitude are available (all zeros means no position).
+    pub fn position_available(&self) -> bool {
+        self.nic() != Nic::Unknown || read_bits(&self.0, 0, 47) != 0
+    }
+
+    /// Type of [`StateVector::altitude`].
+    pub fn altitude_type(&self) -> AltitudeType {
+        match read_bits(&self.0, 47, 1) {
+            0 => AltitudeType::PressureAltitude,
+            _ => AltitudeType::Geometric,
+        }
+    }
+
+    /// Altitude in feet.
+    pub fn altitude(&self) -> Altitude {
+        decode_altitude(read_bits(&self.0, 48, 12))
+    }
+
+    /// Navigation Integrity Category.
+    pub fn nic(&self) -> Nic {
+        Nic::from_bytes(read_bits(&self.0, 60, 4) as u8).unwrap_or(Nic::Unknown)
+    }
+
+    /// Air/Ground state, which defines how velocities are encoded.
+    pub fn air_ground_state(&self) -> AirGroundState {
+        match read_bits(&self.0, 64, 2) {
+            0 => AirGroundState::AirborneSubsonic,
+            1 => AirGroundState::AirborneSupersonic,
+            2 => AirGroundState::OnGround,
+            _ => AirGroundState::Reserved,
+        }
+    }
+
+    /// North velocity in knots (negative means south), only available when airborne.
+    pub fn north_velocity(&self) -> Option<i32> {
+        self.airborne_velocity(67)
+    }
+
+    /// East velocity in knots (negative means west), only available when airborne.
+    pub fn east_velocity(&self) -> Option<i32> {
+        self.airborne_velocity(78)
+    }
+
+    /// Vertical velocity in feet per minute (negative means descending), only available when airborne.
+    pub fn vertical_velocity(&self) -> Option<i32> {
+        if !self.airborne() {
+            return None;
+        }
+        let magnitude = read_bits(&self.0, 91, 9) as i32;
+        if magnitude == 0 {
+            return None;
+        }
+        let value = (magnitude - 1) * UAT_VERT_VELOCITY_FACTOR;
+        match read_bits(&self.0, 90, 1) {
+            0 => Some(value),
+            _ => Some(-value),
+        }
+    }
+
+    /// Source of [`StateVector::vertical_velocity`], only available when airborne.
+    pub fn vertical_velocity_source(&self) -> Option<AltitudeType> {
+        if !self.airborne() {
+            return None;
+        }
+        match read_bits(&self.0, 89, 1) {
+            0 => Some(AltitudeType::Geometric),
+            _ => Some(AltitudeType::PressureAltitude),
+        }
+    }
+
+    /// Ground speed in knots, only available when on ground.
+    pub fn ground_speed(&self) -> Option<u16> {
+        if self.air_ground_state() != AirGroundState::OnGround {
+            return None;
+        }
+        match read_bits(&self.0, 68, 10) {
+            0 => None,
+            x => Some(x as u16 - 1),
+        }
+    }
+
+    /// Track angle or heading in degrees, only available when on ground.
+    pub fn ground_track(&self) -> Option<(GroundTrackType, f32)> {
+        if self.air_ground_state() != AirGroundState::OnGround {
+            return None;
+        }
+        let track_type = match read_bits(&self.0, 78, 2) {
+            1 => GroundTrackType::TrueTrackAngle,
+            2 => GroundTrackType::MagneticHeading,
+            3 => GroundTrackType::TrueHeading,
+            _ => return None,
+        };
+        let degrees = read_bits(&self.0, 80, 9) as f32 * (360.0 / 512.0);
+        Some((track_type, degrees))
+    }
+
+    /// Whether the transmitter is coupled to UTC time (ADS-B targets only).
+    pub fn utc_coupled(&self) -> bool {
+        read_bits(&self.0, 100, 1) != 0
+    }
+
+    /// Uplink feedback (ADS-B targets only).
+    pub fn uplink_feedback(&self) -> u8 {
+        read_bits(&self.0, 101, 3) as u8
+    }
+
+    /// TIS-B site ID (TIS-B targets only).
+    pub fn tisb_site_id(&self) -> u8 {
+        read_bits(&self.0, 100, 4) as u8
+    }
+
+    fn airborne(&self) -> bool {
+        matches!(
+            self.air_ground_state(),
+            AirGroundState::AirborneSubsonic | AirGroundState::AirborneSupersonic
+        )
+    }
+
+    /// Decodes the 11-bit signed velocity starting at bit `offset`.
+    fn airborne_velocity(&self, offset: usize) -> Option<i32> {
+        if !self.airborne() {
+            return None;
+        }
+        let magnitude = read_bits(&self.0, offset + 1, 10) as i32;
+        if magnitude == 0 {
+            return None;
+        }
+        let mut value = magnitude - 1;
+        if self.air_ground_state() == AirGroundState::AirborneSupersonic {
+            value *= 4;
+        }
+        match read_bits(&self.0, offset, 1) {
+            0 => Some(value),
+            _ => Some(-value),
+        }
+    }
+}
+
+/// Type of an altitude (or vertical velocity source).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AltitudeType {
+    PressureAltitude,
+    Geometric,
+}
+
+/// Air/Ground state of the target.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AirGroundState {
+    AirborneSubsonic,
+    AirborneSupersonic,
+    OnGround,
+    Reserved,
+}
+
+/// Type of [`StateVector::ground_track`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GroundTrackType {
+    TrueTrackAngle,
+    MagneticHeading,
+    TrueHeading,
+}
+
+/// Mode Status element (MS). DO-282B - ref 2.2.4.5.4.
+///
+/// | Bits  | Name                                        |
+/// |-------|---------------------------------------------|
+/// | 0-47  | Emitter Category and Call Sign (base 40)    |
+/// | 48-50 | Emergency/Priority Status                   |
+/// | 51-53 | UAT MOPS Version                            |
+/// | 54-55 | SIL                                         |
+/// | 56-61 | Transmit MSO                                |
+/// | 62-63 | Reserved                                    |
+/// | 64-67 | NACp                                        |
+/// | 68-70 | NACv                                        |
+/// | 71    | NICbaro                                     |
+/// | 72-73 | Capability Codes (CDTI, ACAS)               |
+/// | 74-76 | Operational Modes (ACAS RA, IDENT, ATC)     |
+/// | 77    | True/Magnetic Heading                       |
+/// | 78    | Call Sign ID                                |
+/// | 79-95 | Reserved                                    |
+#[derive(BinRead, BinWrite, Debug, PartialEq, Clone, Copy)]
+pub struct ModeStatus(pub [u8; 12]);
+
+/// Base 40 alphabet used by the call sign.
+const BASE40_ALPHABET: &[u8; 40] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ  ..";
+
+impl ModeStatus {
+    /// Emitter category.
+    pub fn emitter_category(&self) -> EmmiterCategory {
+        let value = (read_bits(&self.0, 0, 16) / 1600) % 40;
+        EmmiterCategory::from_bytes(value as u8)
+            .unwrap_or(EmmiterCategory::NoAircraftTypeInformation)
+    }
+
+    /// Call sign (or squawk, see [`ModeStatus::call_sign_id`]), trailing spaces removed.
+    pub fn call_sign(&self) -> String {
+        let mut result = String::with_capacity(8);
+        for (i, offset) in [0, 16, 32].into_iter().enumerate() {
+            let value = read_bits(&self.0, offset, 16);
+            let chars = [(value / 1600) % 40, (value / 40) % 40, value % 40];
+            // first character of the first group is the emitter category
+            let skip = if i == 0 { 1 } else { 0 };
+            for c in &chars[skip..] {
+                result.push(BASE40_ALPHABET[*c as usize] as char);
+            }
+        }
+        result.trim_end().to_string()
+    }
+
+    /// Emergency/priority status.
+    pub fn emergency_status(&self) -> EmergencyPriorityCodeCategory {
+        let value = read_bits(&self.0, 48, 3);
+        EmergencyPriorityCodeCategory::from_bytes(value as u8)
+            .unwrap_or(EmergencyPriorityCodeCategory::NoEmergency)
+    }
+
+    /// UAT MOPS version.
+    pub fn uat_version(&self) -> u8 {
+        read_bits(&self.0, 51, 3) as u8
+    }
+
+    /// Source Integrity Level.
+    pub fn sil(&self) -> u8 {
+        read_bits(&self.0, 54, 2) as u8
+    }
+
+    /// Message Start Opportunity used by the transmitter.
+    pub fn transmit_mso(&self) -> u8 {
+        read_bits(&self.0, 56, 6) as u8
+    }
+
+    /// Navigation Accuracy Category for Position.
+    pub fn nacp(&self) -> Nacp {
+        Nacp::from_bytes(read_bits(&self.0, 64, 4) as u8).unwrap_or(Nacp::Unknown)
+    }
+
+    /// Navigation Accuracy Category for Velocity.
+    pub fn nacv(&self) -> u8 {
+        read_bits(&self.0, 68, 3) as u8
+    }
+
+    /// Barometric altitude integrity (NICbaro).
+    pub fn nic_baro(&self) -> bool {
+        read_bits(&self.0, 71, 1) != 0
+    }
+
+    /// Whether the target has a CDTI installed.
+    pub fn cdti(&self) -> bool {
+        read_bits(&self.0, 72, 1) != 0
+    }
+
+    /// Whether the target has ACAS installed.
+    pub fn acas(&self) -> bool {
+        read_bits(&self.0, 73, 1) != 0
+    }
+
+    /// Whether an ACAS Resolution Advisory is active.
+    pub fn acas_ra_active(&self) -> bool {
+        read_bits(&self.0, 74, 1) != 0
+    }
+
+    /// Whether IDENT is active.
+    pub fn ident_active(&self) -> bool {
+        read_bits(&self.0, 75, 1) != 0
+    }
+
+    /// Whether the target is receiving ATC services.
+    pub fn atc_services(&self) -> bool {
+        read_bits(&self.0, 76, 1) != 0
+    }
+
+    /// Whether headings are magnetic (`false` means true north).
+    pub fn magnetic_heading(&self) -> bool {
+        read_bits(&self.0, 77, 1) != 0
+    }
+
+    /// Whether [`ModeStatus::call_sign`] holds a call sign (`false` means a squawk code).
+    pub fn call_sign_id(&self) -> bool {
+        read_bits(&self.0, 78, 1) != 0
+    }
+}
+
+/// Auxiliary State Vector element (AUX SV). DO-282B - ref 2.2.4.5.5.
+///
+/// | Bits  | Name                  |
+/// |-------|-----------------------|
+/// | 0-11  | Secondary Altitude    |
+/// | 12-39 | Reserved              |
+#[derive(BinRead, BinWrite, Debug, PartialEq, Clone, Copy)]
+pub struct AuxStateVector(pub [u8; 5]);
+
+impl AuxStateVector {
+    /// Secondary altitude in feet. Its type is the opposite of [`StateVector::altitude_type`].
+    pub fn secondary_altitude(&self) -> Altitude {
+        decode_altitude(read_bits(&self.0, 0, 12))
+    }
+}
+
+/// Target State element (TS). DO-282B - ref 2.2.4.5.6.
+///
+/// | Bits  | Name                                  |
+/// |-------|---------------------------------------|
+/// | 0     | Selected Altitude Type (MCP/FCU, FMS) |
+/// | 1-11  | Selected Altitude                     |
+/// | 12-20 | Barometric Pressure Setting           |
+/// | 21    | Selected Heading Status               |
+/// | 22    | Selected Heading Sign                 |
+/// | 23-30 | Selected Heading                      |
+/// | 31    | Mode Indicators Status                |
+/// | 32    | Autopilot Engaged                     |
+/// | 33    | VNAV Mode Engaged                     |
+/// | 34    | Altitude Hold Mode                    |
+/// | 35    | Reserved                              |
+/// | 36    | Approach Mode                         |
+/// | 37    | Reserved                              |
+/// | 38    | LNAV Mode Engaged                     |
+/// | 39    | Reserved                              |
+#[derive(BinRead, BinWrite, Debug, PartialEq, Clone, Copy)]
+pub struct TargetState(pub [u8; 5]);
+
+impl TargetState {
+    /// Whether [`TargetState::selected_altitude`] comes from the FMS (`false` means MCP/FCU).
+    pub fn fms_selected_altitude(&self) -> bool {
+        read_bits(&self.0, 0, 1) != 0
+    }
+
+    /// Selected altitude in feet.
+    pub fn selected_altitude(&self) -> Option<i32> {
+        match read_bits(&self.0, 1, 11) {
+            0 => None,
+            x => Some((x as i32 - 1) * 32),
+        }
+    }
+
+    /// Barometric pressure setting in millibars.
+    pub fn barometric_pressure_setting(&self) -> Option<f32> {
+        match read_bits(&self.0, 12, 9) {
+            0 => None,
+            x => Some(800.0 + (x - 1) as f32 * 0.8),
+        }
+    }
+
+    /// Selected heading in degrees, between -180 and 180.
+    pub fn selected_heading(&self) -> Option<f32> {
+        if read_bits(&self.0, 21, 1) == 0 {
+            return None;
+        }
+        let degrees = read_bits(&self.0, 23, 8) as f32 * (180.0 / 256.0);
+        match read_bits(&self.0, 22, 1) {
+            0 => Some(degrees),
+            _ => Some(degrees - 180.0),
+        }
+    }
+
+    /// Whether the mode indicators (autopilot, VNAV, ...) are valid.
+    pub fn mode_indicators_available(&self) -> bool {
+        read_bits(&self.0, 31, 1) != 0
+    }
+
+    /// Whether the autopilot is engaged.
+    pub fn autopilot_engaged(&self) -> bool {
+        read_bits(&self.0, 32, 1) != 0
+    }
+
+    /// Whether VNAV mode is engaged.
+    pub fn vnav_mode(&self) -> bool {
+        read_bits(&self.0, 33, 1) != 0
+    }
+
+    /// Whether altitude hold mode is engaged.
+    pub fn altitude_hold_mode(&self) -> bool {
+        read_bits(&self.0, 34, 1) != 0
+    }
+
+    /// Whether approach mode is engaged.
+    pub fn approach_mode(&self) -> bool {
+        read_bits(&self.0, 36, 1) != 0
+    }
+
+    /// Whether LNAV mode is engaged.
+    pub fn lnav_mode(&self) -> bool {
+        read_bits(&self.0, 38, 1) != 0
+    }
+}
+
+/// Decodes a 12-bit UAT altitude, where 0 means unavailable.
+fn decode_altitude(raw: u32) -> Altitude {
+    match raw {
+        0 => Altitude::InvalidOrUnknown,
+        x => Altitude::Valid((x as i32 - 1) * UAT_ALTITUDE_FACTOR + UAT_ALTITUDE_OFFSET),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use binrw::io::Cursor;
+
+    /// Writes `value` as `len` bits (MSB first) starting at bit `offset` of `data`.
+    fn write_bits(data: &mut [u8], offset: usize, len: usize, value: u32) {
+        for i in 0..len {
+            let bit = (value >> (len - 1 - i)) & 1;
+            let pos = offset + i;
+            data[pos / 8] |= (bit as u8) << (7 - pos % 8);
+        }
+    }
+
+    fn long_payload() -> [u8; UAT_LONG_PAYLOAD_LEN] {
+        let mut data = [0u8; UAT_LONG_PAYLOAD_LEN];
+        // header: payload type 1, ADS-B with ICAO address AB4549
+        write_bits(&mut data, 0, 5, 1);
+        write_bits(&mut data, 8, 24, 0xAB4549);
+
+        // state vector: 45N 90W, 5000ft pressure altitude, NIC 8
+        let sv = 32;
+        write_bits(&mut data, sv, 23, 0x200000);
+        write_bits(&mut data, sv + 23, 24, 0xC00000);
+        write_bits(&mut data, sv + 48, 12, 241);
+        write_bits(&mut data, sv + 60, 4, 8);
+        // airborne subsonic, 100kt north, 20kt west, 640fpm descending (barometric)
+        write_bits(&mut data, sv + 67, 11, 101);
+        write_bits(&mut data, sv + 78, 11, 0x400 | 21);
+        write_bits(&mut data, sv + 89, 11, 0x400 | 0x200 | 11);
+        write_bits(&mut data, sv + 100, 1, 1);
+
+        // mode status: light aircraft, N825V
+        let ms = 17 * 8;
+        let chars: Vec<u32> = "N825V   "
+            .bytes()
+            .map(|c| BASE40_ALPHABET.iter().position(|&x| x == c).unwrap() as u32)
+            .collect();
+        write_bits(&mut data, ms, 16, 1600 + chars[0] * 40 + chars[1]);
+        write_bits(
+            &mut data,
+            ms + 16,
+            16,
+            chars[2] * 1600 + chars[3] * 40 + chars[4],
+        );
+        write_bits(
+            &mut data,
+            ms + 32,
+            16,
+            chars[5] * 1600 + chars[6] * 40 + chars[7],
+        );
+        write_bits(&mut data, ms + 48, 3, 1);
+        write_bits(&mut data, ms + 51, 3, 2);
+        write_bits(&mut data, ms + 64, 4, 9);
+        write_bits(&mut data, ms + 72, 1, 1);
+        write_bits(&mut data, ms + 78, 1, 1);
+
+        // auxiliary state vector: 5200ft geometric altitude
+        write_bits(&mut data, 29 * 8, 12, 249);
+        data
+    }
+
+    #[test]
+    fn long_payload_works() {
+        let data = long_payload();
+        let payload =
+            UatAdsbPayload::read_args(&mut Cursor::new(data), (UAT_LONG_PAYLOAD_LEN,)).unwrap();
+
+        assert_eq!(payload.header.payload_type_code(), 1);
+        assert_eq!(
+            payload.header.address_qualifier(),
+            AddressQualifier::AdsbWithIcaoAddress
+        );
+        assert_eq!(payload.header.address().value(), 0xAB4549);
+
+        let sv = payload.state_vector;
+        assert!(sv.position_available());
+        assert_eq!(sv.latitude(), 45.0);
+        assert_eq!(sv.longitude(), -90.0);
+        assert_eq!(sv.altitude_type(), AltitudeType::PressureAltitude);
+        assert_eq!(sv.altitude(), Altitude::Valid(5000));
+        assert_eq!(sv.nic(), Nic::Rc0p1Nm);
+        assert_eq!(sv.air_ground_state(), AirGroundState::AirborneSubsonic);
+        assert_eq!(sv.north_velocity(), Some(100));
+        assert_eq!(sv.east_velocity(), Some(-20));
+        assert_eq!(sv.vertical_velocity(), Some(-640));
+        assert_eq!(
+            sv.vertical_velocity_source(),
+            Some(AltitudeType::PressureAltitude)
+        );
+        assert_eq!(sv.ground_speed(), None);
+        assert!(sv.utc_coupled());
+
+        let ms = payload.mode_status().unwrap();
+        assert_eq!(ms.emitter_category(), EmmiterCategory::Light);
+        assert_eq!(ms.call_sign(), "N825V");
+        assert_eq!(
+            ms.emergency_status(),
+            EmergencyPriorityCodeCategory::GeneralEmergency
+        );
+        assert_eq!(ms.uat_version(), 2);
+        assert_eq!(ms.nacp(), Nacp::Epu30M);
+        assert!(ms.cdti());
+        assert!(!ms.acas());
+        assert!(ms.call_sign_id());
+
+        let aux = payload.aux_state_vector().unwrap();
+        assert_eq!(aux.secondary_altitude(), Altitude::Valid(5200));
+        assert_eq!(payload.target_state(), None);
+
+        let mut output = Cursor::new(vec![]);
+        payload.write(&mut output).unwrap();
+        assert_eq!(output.into_inner(), data);
+    }
+
+    #[test]
+    fn basic_payload_works() {
+        let mut data = [0u8; UAT_BASIC_PAYLOAD_LEN];
+        data[..17].copy_from_slice(&long_payload()[..17]);
+        // payload type 0, on ground at 12kt, true heading 90 degrees
+        data[0] = 0;
+        data[12..17].fill(0);
+        write_bits(&mut data, 32 + 64, 2, 2);
+        write_bits(&mut data, 32 + 67, 11, 13);
+        write_bits(&mut data, 32 + 78, 2, 3);
+        write_bits(&mut data, 32 + 80, 9, 128);
+
+        let payload =
+            UatAdsbPayload::read_args(&mut Cursor::new(data), (UAT_BASIC_PAYLOAD_LEN,)).unwrap();
+        assert_eq!(payload.elements.len(), 1);
+        assert_eq!(payload.mode_status(), None);
+        assert_eq!(payload.aux_state_vector(), None);
+
+        let sv = payload.state_vector;
+        assert_eq!(sv.air_ground_state(), AirGroundState::OnGround);
+        assert_eq!(sv.north_velocity(), None);
+        assert_eq!(sv.vertical_velocity(), None);
+        assert_eq!(sv.ground_speed(), Some(12));
+        assert_eq!(
+            sv.ground_track(),
+            Some((GroundTrackType::TrueHeading, 90.0))
+        );
+    }
+
+    /// Writes a Target State element starting at byte `offset` of `data`.
+    fn write_target_state(data: &mut [u8], offset: usize) {
+        let ts = offset * 8;
+        write_bits(data, ts, 1, 1);
+        write_bits(data, ts + 1, 11, 313);
+        write_bits(data, ts + 12, 9, 267);
+        write_bits(data, ts + 21, 1, 1);
+        write_bits(data, ts + 23, 8, 128);
+        write_bits(data, ts + 31, 2, 0b11);
+        write_bits(data, ts + 38, 1, 1);
+    }
+
+    #[test]
+    fn target_state_works() {
+        let mut data = [0u8; 5];
+        write_target_state(&mut data, 0);
+
+        let ts = TargetState(data);
+        assert!(ts.fms_selected_altitude());
+        assert_eq!(ts.selected_altitude(), Some(9984));
+        assert!((ts.barometric_pressure_setting().unwrap() - 1012.8).abs() < 0.01);
+        assert_eq!(ts.selected_heading(), Some(90.0));
+        assert!(ts.mode_indicators_available());
+        assert!(ts.autopilot_engaged());
+        assert!(!ts.vnav_mode());
+        assert!(ts.lnav_mode());
+    }
+
+    #[test]
+    fn target_state_element_works() {
+        let mut expected = [0u8; 5];
+        write_target_state(&mut expected, 0);
+        let expected = TargetState(expected);
+
+        // HDR SV TS AUXSV: Target State in bytes 25-29
+        for payload_type in [5, 6] {
+            let mut data = long_payload();
+            data[0] = 0;
+            write_bits(&mut data, 0, 5, payload_type);
+            data[17..29].fill(0);
+            write_target_state(&mut data, 24);
+
+            let payload =
+                UatAdsbPayload::read_args(&mut Cursor::new(data), (UAT_LONG_PAYLOAD_LEN,)).unwrap();
+            assert_eq!(payload.mode_status(), None);
+            assert_eq!(payload.target_state(), Some(expected));
+            assert_eq!(
+                payload.aux_state_vector().unwrap().secondary_altitude(),
+                Altitude::Valid(5200)
+            );
+        }
+
+        // HDR SV MS TS: Target State in bytes 30-34
+        let mut data = long_payload();
+        data[0] = 0;
+        write_bits(&mut data, 0, 5, 3);
+        data[29..].fill(0);
+        write_target_state(&mut data, 29);
+
+        let payload =
+            UatAdsbPayload::read_args(&mut Cursor::new(data), (UAT_LONG_PAYLOAD_LEN,)).unwrap();
+        assert_eq!(payload.mode_status().unwrap().call_sign(), "N825V");
+        assert_eq!(payload.aux_state_vector(), None);
+        assert_eq!(payload.target_state(), Some(expected));
+    }
+}
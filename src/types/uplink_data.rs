@@ -1,10 +1,463 @@
 //! Uplink Data Payload. 560-1058-00 Rev A - ref 3.3.x
+//!
+//! The payload is a UAT Ground Uplink message, as specified by RTCA DO-282B - ref 2.2.3.2.
+//!
+//! | Byte # | Name                 | Size  |
+//! |--------|----------------------|-------|
+//! | 1-8    | UAT-Specific Header  | 8     |
+//! | 9-432  | Application Data     | 424   |
 
-use binrw::BinRead;
+use alloc::vec;
+use alloc::vec::Vec;
 
-#[derive(BinRead, Debug)]
-#[br(little)]
+use binrw::{BinRead, BinWrite};
+
+use super::read_bits;
+use crate::error::Gdl90Error;
+
+/// Length of the Application Data of an uplink message.
+pub const UPLINK_APPLICATION_DATA_LEN: usize = 424;
+
+/// Information Frame type of FIS-B APDUs.
+pub const INFO_FRAME_TYPE_FISB: u8 = 0;
+
+#[derive(BinRead, BinWrite, Debug, PartialEq)]
+#[brw(little)]
 pub struct UplinkPayload {
-    pub uat_specific_header: u8,
-    pub payload: [u8; 424],
+    /// UAT-Specific Header.
+    pub header: UplinkHeader,
+
+    /// Application Data, holding the Information Frames, see [`UplinkPayload::information_frames`].
+    pub application_data: [u8; UPLINK_APPLICATION_DATA_LEN],
+}
+
+impl UplinkPayload {
+    /// Splits the Application Data into its Information Frames.
+    ///
+    /// Returns an empty list if [`UplinkHeader::app_data_valid`] is not set.
+    /// Decoding stops at the first empty frame or at a frame overrunning the Application Data.
+    pub fn information_frames(&self) -> Vec<InformationFrame> {
+        information_frames(&self.header, &self.application_data)
+    }
+
+    /// Decodes every FIS-B APDU of the Application Data.
+    pub fn fisb_apdus(&self) -> Vec<Result<FisbApdu, Gdl90Error>> {
+        fisb_apdus(&self.header, &self.application_data)
+    }
+}
+
+/// Splits `application_data` into its Information Frames, see [`UplinkPayload::information_frames`].
+pub(crate) fn information_frames(
+    header: &UplinkHeader,
+    application_data: &[u8],
+) -> Vec<InformationFrame> {
+    let mut frames = vec![];
+    if !header.app_data_valid() {
+        return frames;
+    }
+
+    let mut data = application_data;
+    while data.len() >= 2 {
+        let length = ((data[0] as usize) << 1) | (data[1] as usize >> 7);
+        let frame_type = data[1] & 0x0F;
+        if length == 0 && frame_type == 0 {
+            // no more frames
+            break;
+        }
+        if 2 + length > data.len() {
+            break;
+        }
+        frames.push(InformationFrame {
+            frame_type,
+            data: data[2..2 + length].to_vec(),
+        });
+        data = &data[2 + length..];
+    }
+    frames
+}
+
+/// Decodes every FIS-B APDU of `application_data`, see [`UplinkPayload::fisb_apdus`].
+pub(crate) fn fisb_apdus(
+    header: &UplinkHeader,
+    application_data: &[u8],
+) -> Vec<Result<FisbApdu, Gdl90Error>> {
+    information_frames(header, application_data)
+        .iter()
+        .filter_map(InformationFrame::fisb_apdu)
+        .collect()
+}
+
+/// UAT-Specific Header of an uplink message. DO-282B - ref 2.2.3.2.2.
+///
+/// | Bits  | Name                          |
+/// |-------|-------------------------------|
+/// | 0-22  | Ground Station Latitude       |
+/// | 23-46 | Ground Station Longitude      |
+/// | 47    | Position Valid                |
+/// | 48    | UTC Coupled                   |
+/// | 49    | Reserved                      |
+/// | 50    | Application Data Valid        |
+/// | 51-55 | Slot ID                       |
+/// | 56-59 | TIS-B Site ID                 |
+/// | 60-63 | Reserved                      |
+#[derive(BinRead, BinWrite, Debug, PartialEq, Clone, Copy)]
+pub struct UplinkHeader(pub [u8; 8]);
+
+impl UplinkHeader {
+    /// Ground station latitude in degrees.
+    pub fn latitude(&self) -> f32 {
+        let degrees = read_bits(&self.0, 0, 23) as f32 * (360.0 / (1 << 24) as f32);
+        if degrees > 90.0 {
+            degrees - 180.0
+        } else {
+            degrees
+        }
+    }
+
+    /// Ground station longitude in degrees.
+    pub fn longitude(&self) -> f32 {
+        let degrees = read_bits(&self.0, 23, 24) as f32 * (360.0 / (1 << 24) as f32);
+        if degrees > 180.0 {
+            degrees - 360.0
+        } else {
+            degrees
+        }
+    }
+
+    /// Whether the ground station position is valid.
+    pub fn position_valid(&self) -> bool {
+        self.0[5] & 0x01 != 0
+    }
+
+    /// Whether the ground station is coupled to UTC time.
+    pub fn utc_coupled(&self) -> bool {
+        self.0[6] & 0x80 != 0
+    }
+
+    /// Whether the Application Data holds valid Information Frames.
+    pub fn app_data_valid(&self) -> bool {
+        self.0[6] & 0x20 != 0
+    }
+
+    /// Transmission slot used by the ground station.
+    pub fn slot_id(&self) -> u8 {
+        self.0[6] & 0x1F
+    }
+
+    /// TIS-B site ID of the ground station.
+    pub fn tisb_site_id(&self) -> u8 {
+        self.0[7] >> 4
+    }
+}
+
+/// Information Frame of the uplink Application Data. DO-282B - ref 2.2.3.2.3.
+///
+/// | Bits  | Name          |
+/// |-------|---------------|
+/// | 0-8   | Length        |
+/// | 9-11  | Reserved      |
+/// | 12-15 | Frame Type    |
+/// | 16-   | Frame Data    |
+#[derive(Debug, PartialEq, Clone)]
+pub struct InformationFrame {
+    /// Frame type, [`INFO_FRAME_TYPE_FISB`] for FIS-B APDUs.
+    pub frame_type: u8,
+
+    /// Frame data.
+    pub data: Vec<u8>,
+}
+
+impl InformationFrame {
+    /// Decodes the frame data as a FIS-B APDU, or `None` if this is not a FIS-B frame.
+    pub fn fisb_apdu(&self) -> Option<Result<FisbApdu, Gdl90Error>> {
+        if self.frame_type != INFO_FRAME_TYPE_FISB {
+            return None;
+        }
+        Some(FisbApdu::decode(&self.data))
+    }
+}
+
+/// FIS-B Application Protocol Data Unit. DO-267A - ref 2.2.2.
+///
+/// | Bits  | Name                          |
+/// |-------|-------------------------------|
+/// | 0     | Application Method flag (A)   |
+/// | 1     | Geo Location flag (G)         |
+/// | 2     | Provider Specific flag (P)    |
+/// | 3-13  | Product ID                    |
+/// | 14    | Segmentation flag (S)         |
+/// | 15-16 | Time Option                   |
+/// | 17-   | Time, see [`FisbTime`]        |
+///
+/// When the segmentation flag is set, the time is followed by the 10-bit Product File ID,
+/// the 9-bit Product File Length and the 9-bit APDU Number.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FisbApdu {
+    pub a_flag: bool,
+    pub g_flag: bool,
+    pub p_flag: bool,
+
+    /// FIS-B product ID, eg. 413 for Generic Textual Data.
+    pub product_id: u16,
+
+    /// Product time stamp.
+    pub time: FisbTime,
+
+    /// Segmentation info, if the product is split across several APDUs.
+    pub segment: Option<FisbSegment>,
+
+    /// APDU payload.
+    pub payload: Vec<u8>,
+}
+
+impl FisbApdu {
+    /// Decodes an APDU from the data of an Information Frame.
+    pub fn decode(data: &[u8]) -> Result<Self, Gdl90Error> {
+        if data.len() < 3 {
+            return Err(Gdl90Error::Truncated);
+        }
+
+        let s_flag = read_bits(data, 14, 1) != 0;
+        let t_opt = read_bits(data, 15, 2);
+        // bit length of the header up to the end of the time
+        let time_end = [28, 34, 37, 43][t_opt as usize];
+        if data.len() * 8 < time_end {
+            return Err(Gdl90Error::Truncated);
+        }
+
+        let time = match t_opt {
+            0 => FisbTime {
+                month: None,
+                day: None,
+                hours: read_bits(data, 17, 5) as u8,
+                minutes: read_bits(data, 22, 6) as u8,
+                seconds: None,
+            },
+            1 => FisbTime {
+                month: None,
+                day: None,
+                hours: read_bits(data, 17, 5) as u8,
+                minutes: read_bits(data, 22, 6) as u8,
+                seconds: Some(read_bits(data, 28, 6) as u8),
+            },
+            2 => FisbTime {
+                month: Some(read_bits(data, 17, 4) as u8),
+                day: Some(read_bits(data, 21, 5) as u8),
+                hours: read_bits(data, 26, 5) as u8,
+                minutes: read_bits(data, 31, 6) as u8,
+                seconds: None,
+            },
+            _ => FisbTime {
+                month: Some(read_bits(data, 17, 4) as u8),
+                day: Some(read_bits(data, 21, 5) as u8),
+                hours: read_bits(data, 26, 5) as u8,
+                minutes: read_bits(data, 31, 6) as u8,
+                seconds: Some(read_bits(data, 37, 6) as u8),
+            },
+        };
+
+        let mut offset = time_end;
+        let segment = if s_flag {
+            if data.len() * 8 < offset + 28 {
+                return Err(Gdl90Error::Truncated);
+            }
+            let segment = FisbSegment {
+                product_file_id: read_bits(data, offset, 10) as u16,
+                product_file_length: read_bits(data, offset + 10, 9) as u16,
+                apdu_number: read_bits(data, offset + 19, 9) as u16,
+            };
+            offset += 28;
+            Some(segment)
+        } else {
+            None
+        };
+
+        let header_len = offset.div_ceil(8);
+
+        Ok(Self {
+            a_flag: read_bits(data, 0, 1) != 0,
+            g_flag: read_bits(data, 1, 1) != 0,
+            p_flag: read_bits(data, 2, 1) != 0,
+            product_id: read_bits(data, 3, 11) as u16,
+            time,
+            segment,
+            payload: data[header_len..].to_vec(),
+        })
+    }
+}
+
+/// Builds an APDU for tests, time stamped 12:00 with every flag cleared.
+#[cfg(test)]
+pub(crate) fn test_apdu(product_id: u16, segment: Option<FisbSegment>, payload: &[u8]) -> FisbApdu {
+    FisbApdu {
+        a_flag: false,
+        g_flag: false,
+        p_flag: false,
+        product_id,
+        time: FisbTime {
+            month: None,
+            day: None,
+            hours: 12,
+            minutes: 0,
+            seconds: None,
+        },
+        segment,
+        payload: payload.to_vec(),
+    }
+}
+
+/// Time stamp of a FIS-B APDU. Which fields are present depends on the APDU Time Option.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct FisbTime {
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: Option<u8>,
+}
+
+/// Segmentation info of a FIS-B APDU.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct FisbSegment {
+    /// Identifies the product file the APDU belongs to.
+    pub product_file_id: u16,
+
+    /// Number of APDUs of the product file.
+    pub product_file_length: u16,
+
+    /// Position of the APDU in the product file, starting at 1.
+    pub apdu_number: u16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(header: [u8; 8], frames: &[(u8, &[u8])]) -> UplinkPayload {
+        let mut application_data = [0u8; UPLINK_APPLICATION_DATA_LEN];
+        let mut pos = 0;
+        for (frame_type, data) in frames {
+            application_data[pos] = (data.len() >> 1) as u8;
+            application_data[pos + 1] = ((data.len() as u8 & 1) << 7) | frame_type;
+            application_data[pos + 2..pos + 2 + data.len()].copy_from_slice(data);
+            pos += 2 + data.len();
+        }
+        UplinkPayload {
+            header: UplinkHeader(header),
+            application_data,
+        }
+    }
+
+    #[test]
+    fn header_works() {
+        // 45N 90W, position valid, UTC coupled, app data valid, slot 5, TIS-B site 3
+        let header = UplinkHeader([0x40, 0x00, 0x01, 0x80, 0x00, 0x01, 0xA5, 0x30]);
+        assert_eq!(header.latitude(), 45.0);
+        assert_eq!(header.longitude(), -90.0);
+        assert!(header.position_valid());
+        assert!(header.utc_coupled());
+        assert!(header.app_data_valid());
+        assert_eq!(header.slot_id(), 5);
+        assert_eq!(header.tisb_site_id(), 3);
+    }
+
+    #[test]
+    fn information_frames_work() {
+        // product 413, hours and minutes (12:34), payload "AB"
+        let apdu = [0x06, 0x74, 0x32, 0x20, b'A', b'B'];
+        let uplink = payload([0, 0, 0, 0, 0, 0, 0x20, 0], &[(0, &apdu), (15, &[1, 2, 3])]);
+
+        let frames = uplink.information_frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1].frame_type, 15);
+        assert_eq!(frames[1].data, [1, 2, 3]);
+        assert!(frames[1].fisb_apdu().is_none());
+
+        let apdus = uplink.fisb_apdus();
+        assert_eq!(apdus.len(), 1);
+        let apdu = apdus[0].as_ref().unwrap();
+        assert_eq!(apdu.product_id, 413);
+        assert_eq!(
+            apdu.time,
+            FisbTime {
+                month: None,
+                day: None,
+                hours: 12,
+                minutes: 34,
+                seconds: None,
+            }
+        );
+        assert_eq!(apdu.segment, None);
+        assert_eq!(apdu.payload, b"AB");
+
+        // application data not valid
+        let uplink = payload([0; 8], &[(0, &apdu_bytes())]);
+        assert!(uplink.information_frames().is_empty());
+    }
+
+    fn apdu_bytes() -> [u8; 10] {
+        // A and G flags, product 8, segmented, month/day/hours/minutes/seconds
+        // 2023-12-31 23:59:58, file 0x155, 3 APDUs, APDU 2
+        let mut data = [0u8; 10];
+        let fields: [(usize, u32); 13] = [
+            (1, 1),
+            (1, 1),
+            (1, 0),
+            (11, 8),
+            (1, 1),
+            (2, 3),
+            (4, 12),
+            (5, 31),
+            (5, 23),
+            (6, 59),
+            (6, 58),
+            (10, 0x155),
+            (9, 3),
+        ];
+        let mut offset = 0;
+        for (len, value) in fields.into_iter().chain([(9, 2)]) {
+            for i in 0..len {
+                let pos = offset + i;
+                data[pos / 8] |= (((value >> (len - 1 - i)) & 1) as u8) << (7 - pos % 8);
+            }
+            offset += len;
+        }
+        data[9] = 0xAA;
+        data
+    }
+
+    #[test]
+    fn segmented_apdu_works() {
+        let apdu = FisbApdu::decode(&apdu_bytes()).unwrap();
+        assert!(apdu.a_flag);
+        assert!(apdu.g_flag);
+        assert!(!apdu.p_flag);
+        assert_eq!(apdu.product_id, 8);
+        assert_eq!(
+            apdu.time,
+            FisbTime {
+                month: Some(12),
+                day: Some(31),
+                hours: 23,
+                minutes: 59,
+                seconds: Some(58),
+            }
+        );
+        assert_eq!(
+            apdu.segment,
+            Some(FisbSegment {
+                product_file_id: 0x155,
+                product_file_length: 3,
+                apdu_number: 2,
+            })
+        );
+        assert_eq!(apdu.payload, [0xAA]);
+
+        for len in 0..9 {
+            assert!(matches!(
+                FisbApdu::decode(&apdu_bytes()[..len]),
+                Err(Gdl90Error::Truncated)
+            ));
+        }
+    }
 }
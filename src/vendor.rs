@@ -0,0 +1,253 @@
+//! Vendor (non-standard) GDL90 messages.
+//!
+//! Messages whose ID is not supported by this crate are read as
+//! [`Gdl90DatalinkMessage::Unknown`]. Downstream crates can decode them by implementing
+//! [`VendorMessage`] and registering their types in a [`VendorRegistry`].
+//!
+//! ## Example
+//! ```
+//! use gdl90::error::Gdl90Error;
+//! use gdl90::vendor::{VendorMessage, VendorRegistry};
+//!
+//! #[derive(Debug, PartialEq)]
+//! struct Battery {
+//!     percent: u8,
+//! }
+//!
+//! impl VendorMessage for Battery {
+//!     const ID: u8 = 0x70;
+//!
+//!     fn decode(payload: &[u8]) -> Result<Self, Gdl90Error> {
+//!         match payload {
+//!             [percent] => Ok(Battery { percent: *percent }),
+//!             _ => Err(Gdl90Error::Truncated),
+//!         }
+//!     }
+//!
+//!     fn encode(&self) -> Vec<u8> {
+//!         vec![self.percent]
+//!     }
+//! }
+//!
+//! #[derive(Debug, PartialEq)]
+//! enum MyMessage {
+//!     Battery(Battery),
+//! }
+//!
+//! impl From<Battery> for MyMessage {
+//!     fn from(battery: Battery) -> Self {
+//!         MyMessage::Battery(battery)
+//!     }
+//! }
+//!
+//! let mut registry = VendorRegistry::<MyMessage>::new();
+//! registry.register::<Battery>();
+//!
+//! let parsed = gdl90::read_raw(&Battery { percent: 85 }.to_frame()).unwrap();
+//! let decoded = registry.decode(&parsed.message_data).unwrap().unwrap();
+//! assert_eq!(decoded, MyMessage::Battery(Battery { percent: 85 }));
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::datalink::Gdl90DatalinkMessage;
+use crate::error::Gdl90Error;
+
+/// Vendor message carried in a [`Gdl90DatalinkMessage::Unknown`].
+pub trait VendorMessage: Sized {
+    /// Message ID. Should not be one supported by [`Gdl90DatalinkMessage`].
+    const ID: u8;
+
+    /// Decodes the message from its data, without the message ID and CRC.
+    fn decode(payload: &[u8]) -> Result<Self, Gdl90Error>;
+
+    /// Encodes the message data, without the message ID and CRC.
+    fn encode(&self) -> Vec<u8>;
+
+    /// Builds the [`Gdl90DatalinkMessage::Unknown`] holding this message.
+    fn to_datalink_message(&self) -> Gdl90DatalinkMessage {
+        Gdl90DatalinkMessage::Unknown {
+            id: Self::ID,
+            payload: self.encode(),
+        }
+    }
+
+    /// Builds the complete GDL90 frame of this message, see [`crate::frame`].
+    fn to_frame(&self) -> Vec<u8> {
+        crate::frame(Self::ID, &self.encode())
+    }
+
+    /// Decodes the message from `message`, returning `None` if it is not an
+    /// [`Gdl90DatalinkMessage::Unknown`] with [`Self::ID`].
+    fn from_datalink_message(message: &Gdl90DatalinkMessage) -> Option<Result<Self, Gdl90Error>> {
+        match message {
+            Gdl90DatalinkMessage::Unknown { id, payload } if *id == Self::ID => {
+                Some(Self::decode(payload))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Function decoding the data of a vendor message, see [`VendorRegistry::register_fn`].
+pub type VendorDecodeFn<T> = fn(&[u8]) -> Result<T, Gdl90Error>;
+
+/// Decoders of vendor messages by message ID, producing a common type `T` (eg. an enum of the
+/// application messages).
+pub struct VendorRegistry<T> {
+    decoders: BTreeMap<u8, VendorDecodeFn<T>>,
+}
+
+impl<T> VendorRegistry<T> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self {
+            decoders: BTreeMap::new(),
+        }
+    }
+
+    /// Registers `M` to decode messages with ID [`VendorMessage::ID`], replacing any previous
+    /// decoder for it.
+    pub fn register<M>(&mut self) -> &mut Self
+    where
+        M: VendorMessage,
+        T: From<M>,
+    {
+        self.register_fn(M::ID, |payload| M::decode(payload).map(T::from))
+    }
+
+    /// Registers `decode` to decode messages with ID `id`, replacing any previous decoder for it.
+    pub fn register_fn(&mut self, id: u8, decode: VendorDecodeFn<T>) -> &mut Self {
+        self.decoders.insert(id, decode);
+        self
+    }
+
+    /// Whether a decoder is registered for `id`.
+    pub fn contains(&self, id: u8) -> bool {
+        self.decoders.contains_key(&id)
+    }
+
+    /// Decodes the data of a message with ID `id`, failing with
+    /// [`Gdl90Error::UnknownMessageId`] if no decoder is registered for it.
+    pub fn decode_payload(&self, id: u8, payload: &[u8]) -> Result<T, Gdl90Error> {
+        match self.decoders.get(&id) {
+            Some(decode) => decode(payload),
+            None => Err(Gdl90Error::UnknownMessageId(id)),
+        }
+    }
+
+    /// Decodes `message`, returning `None` if it is not a [`Gdl90DatalinkMessage::Unknown`].
+    pub fn decode(&self, message: &Gdl90DatalinkMessage) -> Option<Result<T, Gdl90Error>> {
+        match message {
+            Gdl90DatalinkMessage::Unknown { id, payload } => {
+                Some(self.decode_payload(*id, payload))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<T> Default for VendorRegistry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for VendorRegistry<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VendorRegistry")
+            .field("ids", &self.decoders.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_raw;
+
+    #[derive(Debug, PartialEq)]
+    struct Ping(u16);
+
+    impl VendorMessage for Ping {
+        const ID: u8 = 0x70;
+
+        fn decode(payload: &[u8]) -> Result<Self, Gdl90Error> {
+            match payload {
+                [msb, lsb] => Ok(Ping(u16::from_be_bytes([*msb, *lsb]))),
+                _ => Err(Gdl90Error::Truncated),
+            }
+        }
+
+        fn encode(&self) -> Vec<u8> {
+            self.0.to_be_bytes().to_vec()
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Vendor {
+        Ping(Ping),
+        Raw(Vec<u8>),
+    }
+
+    impl From<Ping> for Vendor {
+        fn from(ping: Ping) -> Self {
+            Vendor::Ping(ping)
+        }
+    }
+
+    #[test]
+    fn vendor_message_works() {
+        let frame = Ping(0x7E7D).to_frame();
+        let parsed = read_raw(&frame).unwrap();
+        assert_eq!(parsed.message_data, Ping(0x7E7D).to_datalink_message());
+        assert_eq!(crate::write_raw(&parsed).unwrap(), frame);
+
+        assert_eq!(
+            Ping::from_datalink_message(&parsed.message_data).map(Result::unwrap),
+            Some(Ping(0x7E7D))
+        );
+
+        let other = Gdl90DatalinkMessage::Unknown {
+            id: 0x71,
+            payload: vec![0x00, 0x01],
+        };
+        assert!(Ping::from_datalink_message(&other).is_none());
+    }
+
+    #[test]
+    fn registry_works() {
+        let mut registry = VendorRegistry::<Vendor>::new();
+        registry
+            .register::<Ping>()
+            .register_fn(0x72, |payload| Ok(Vendor::Raw(payload.to_vec())));
+        assert!(registry.contains(0x70));
+        assert!(registry.contains(0x72));
+        assert!(!registry.contains(0x71));
+
+        let decode = |frame: &[u8]| registry.decode(&read_raw(frame).unwrap().message_data);
+
+        assert_eq!(
+            decode(&Ping(42).to_frame()).unwrap().unwrap(),
+            Vendor::Ping(Ping(42))
+        );
+        assert_eq!(
+            decode(&crate::frame(0x72, &[0x01, 0x02])).unwrap().unwrap(),
+            Vendor::Raw(vec![0x01, 0x02])
+        );
+        assert!(matches!(
+            decode(&crate::frame(0x70, &[0x01])),
+            Some(Err(Gdl90Error::Truncated))
+        ));
+        assert!(matches!(
+            decode(&crate::frame(0x71, &[])),
+            Some(Err(Gdl90Error::UnknownMessageId(0x71)))
+        ));
+
+        // supported messages are not vendor messages
+        let heartbeat = b"\x7E\x00\x81\x41\xDB\xD0\x08\x02\xB3\x8B\x7E";
+        assert!(decode(heartbeat).is_none());
+    }
+}